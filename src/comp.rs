@@ -0,0 +1,341 @@
+use crate::com::*;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Shell {
+    #[default]
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Shell::*;
+        match self {
+            Bash => write!(f, "bash"),
+            Zsh => write!(f, "zsh"),
+            Fish => write!(f, "fish"),
+        }
+    }
+}
+
+impl<'a> Parse<'a> for Shell {
+    fn parse(i: Arg<'a>) -> Result<Self, ParseErr<'a>> {
+        use Shell::*;
+        match i {
+            "bash" => Ok(Bash),
+            "zsh" => Ok(Zsh),
+            "fish" => Ok(Fish),
+            _ => Err(ParseErr {
+                i,
+                ty: Self::desc(),
+                e: "Expected one of 'bash', 'zsh' or 'fish'".to_owned(),
+            }),
+        }
+    }
+
+    fn desc() -> &'static str {
+        stringify!(Shell)
+    }
+}
+
+pub fn takes_value(ty: &str) -> bool {
+    ty != FLAG
+}
+
+/// Option keys of a usage table, including the negated form of flags.
+pub fn keys(r: &[[String; 4]]) -> Vec<[String; 2]> {
+    r.iter()
+        .flat_map(|r| {
+            let k = [r[0].to_owned(), r[2].to_owned()];
+            match takes_value(&r[1]) {
+                true => vec![k],
+                false => vec![k, [neg(&r[0]), r[2].to_owned()]],
+            }
+        })
+        .chain([
+            [format!("{PFX}help"), "Print help.".to_owned()],
+            [
+                format!("{PFX}interactive"),
+                "Fill in the options as a form.".to_owned(),
+            ],
+        ])
+        .collect()
+}
+
+/// Option keys of a node's usage table, without the `--help` and `--interactive` that `keys` adds for leaves.
+pub fn node_keys(r: &[[String; 4]]) -> Vec<[String; 2]> {
+    let b = [format!("{PFX}help"), format!("{PFX}interactive")];
+    keys(r)
+        .into_iter()
+        .filter(|[k, _]| !b.contains(k))
+        .collect()
+}
+
+struct Entry {
+    path: String,
+    acts: Vec<[String; 2]>,
+    opts: Vec<[String; 2]>,
+    vals: Vec<String>,
+    /// Whether this is a leaf, whose other words are completed by the binary.
+    leaf: bool,
+}
+
+fn entries(n: &[(String, Node)]) -> Vec<Entry> {
+    n.iter()
+        .map(|(path, n)| match n {
+            Node::Acts(a, o) => Entry {
+                path: path.to_owned(),
+                acts: a
+                    .iter()
+                    .map(|[k, d]| [k.to_string(), d.to_string()])
+                    .collect(),
+                opts: node_keys(o),
+                vals: o
+                    .iter()
+                    .filter(|r| takes_value(&r[1]))
+                    .map(|r| r[0].to_owned())
+                    .collect(),
+                leaf: false,
+            },
+            Node::Args(a) => Entry {
+                path: path.to_owned(),
+                acts: vec![],
+                opts: keys(a),
+                vals: a
+                    .iter()
+                    .filter(|r| takes_value(&r[1]))
+                    .map(|r| r[0].to_owned())
+                    .collect(),
+                leaf: true,
+            },
+        })
+        .collect()
+}
+
+fn fn_name(bin: &str) -> String {
+    bin.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn words(e: &Entry) -> String {
+    e.acts
+        .iter()
+        .map(|[k, _]| k)
+        .chain(e.opts.iter().map(|[k, _]| k))
+        .join(" ")
+}
+
+fn bash(bin: &str, e: &[Entry]) -> String {
+    let f = fn_name(bin);
+    let cases = e
+        .iter()
+        .map(|e| {
+            format!(
+                "        {}) w={}; v={}; l={} ;;\n",
+                quote(&e.path),
+                quote(&words(e)),
+                quote(&e.vals.join(" ")),
+                if e.leaf { "1" } else { "''" }
+            )
+        })
+        .join("");
+    format!(
+        r#"__{f}_at() {{
+    case "$1" in
+{cases}        *) return 1 ;;
+    esac
+}}
+
+_{f}() {{
+    local cur prev p w v l e i
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    p=""
+    __{f}_at "" || return
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            --) e=1; break ;;
+            =) ((i++)); continue ;;
+            -*)
+                # The value of an option is skipped, unless bash split it off at `=`.
+                [[ " $v " == *" ${{COMP_WORDS[i]}} "* && ${{COMP_WORDS[i+1]}} != = ]] && ((i++))
+                continue
+                ;;
+        esac
+        __{f}_at "${{p:+$p }}${{COMP_WORDS[i]}}" || break
+        p="${{p:+$p }}${{COMP_WORDS[i]}}"
+    done
+    if [[ " $v " == *" $prev "* || -n $l && ( -n $e || $cur != -* ) ]]; then
+        mapfile -t COMPREPLY < <("${{COMP_WORDS[0]}}" {COMPLETE} "${{COMP_WORDS[@]:1:COMP_CWORD}}")
+        return
+    fi
+    COMPREPLY=($(compgen -W "$w" -- "$cur"))
+}}
+complete -F _{f} {bin}
+"#
+    )
+}
+
+fn zsh(bin: &str, e: &[Entry]) -> String {
+    let f = fn_name(bin);
+    let cases = e
+        .iter()
+        .map(|e| {
+            format!(
+                "        {}) w=({}); v=({}); l={} ;;\n",
+                quote(&e.path),
+                e.acts
+                    .iter()
+                    .chain(e.opts.iter())
+                    .map(|[k, d]| quote(&format!("{}:{}", k, d.replace(':', r"\:"))))
+                    .join(" "),
+                e.vals.iter().map(|v| quote(v)).join(" "),
+                if e.leaf { "1" } else { "''" }
+            )
+        })
+        .join("");
+    format!(
+        r#"#compdef {bin}
+
+__{f}_at() {{
+    case "$1" in
+{cases}        *) return 1 ;;
+    esac
+}}
+
+_{f}() {{
+    local p l e i
+    local -a w v
+    p=""
+    __{f}_at "" || return
+    for ((i = 2; i < CURRENT; i++)); do
+        [[ ${{words[i]}} == -- ]] && e=1 && break
+        if [[ ${{words[i]}} == -* ]]; then
+            (( ${{v[(Ie)${{words[i]}}]}} )) && (( i++ ))
+            continue
+        fi
+        __{f}_at "${{p:+$p }}${{words[i]}}" || break
+        p="${{p:+$p }}${{words[i]}}"
+    done
+    if (( ${{v[(Ie)${{words[CURRENT-1]}}]}} )) || [[ -n $l && ( -n $e || ${{words[CURRENT]}} != -* ) ]]; then
+        compadd -- ${{(f)"$(${{words[1]}} {COMPLETE} "${{(@)words[2,CURRENT]}}")"}}
+        return
+    fi
+    _describe -t commands {bin} w
+}}
+
+if [ "$funcstack[1]" = "_{f}" ]; then
+    _{f} "$@"
+else
+    compdef _{f} {bin}
+fi
+"#
+    )
+}
+
+fn fish(bin: &str, e: &[Entry]) -> String {
+    let f = fn_name(bin);
+    let c = quote(&format!(
+        "({bin} {COMPLETE} (commandline -opc)[2..-1] (commandline -ct))"
+    ));
+    let lines = e
+        .iter()
+        .flat_map(|e| {
+            let n = quote(&format!("__{f}_at {}", e.path));
+            let acts = e.acts.iter().map({
+                let n = n.clone();
+                move |[k, d]| format!("complete -c {bin} -n {n} -a {} -d {}", quote(k), quote(d))
+            });
+            let opts = e.opts.iter().map({
+                let c = c.clone();
+                move |[k, d]| {
+                    format!(
+                        "complete -c {bin} -n {n} -l {} -d {}{}",
+                        quote(k.trim_start_matches(PFX)),
+                        quote(d),
+                        if e.vals.contains(k) {
+                            format!(" -r -a {c}")
+                        } else {
+                            "".to_owned()
+                        }
+                    )
+                }
+            });
+            let vals = e.leaf.then(|| {
+                format!(
+                    "complete -c {bin} -n {} -a {}",
+                    quote(&format!(
+                        "__{f}_at {}; and not string match -q -- '-*' (commandline -ct)",
+                        e.path
+                    )),
+                    c
+                )
+            });
+            acts.chain(opts).chain(vals).collect_vec()
+        })
+        .join("\n");
+    let paths = e.iter().map(|e| quote(&e.path)).join(" ");
+    let cases = e
+        .iter()
+        .filter(|e| !e.vals.is_empty())
+        .map(|e| {
+            format!(
+                "        case {}\n            string split ' ' -- {}\n",
+                quote(&e.path),
+                quote(&e.vals.join(" "))
+            )
+        })
+        .join("");
+    format!(
+        r#"function __{f}_vals
+    switch "$argv"
+{cases}    end
+end
+
+function __{f}_path
+    set -l k {paths}
+    set -l p
+    set -l s
+    for w in (commandline -opc)[2..-1]
+        if set -q s[1]
+            set -e s
+            continue
+        end
+        test "$w" = --; and break
+        if string match -q -- '-*' $w
+            # The value of an option is skipped.
+            contains -- $w (__{f}_vals $p); and set s 1
+            continue
+        end
+        contains -- (string join ' ' $p $w) $k; or break
+        set -a p $w
+    end
+    string join ' ' $p
+end
+
+function __{f}_at
+    set -l p (__{f}_path)
+    test "$p" = "$argv"
+end
+
+complete -c {bin} -f
+{lines}
+"#
+    )
+}
+
+pub fn script(sh: Shell, bin: &str, n: &[(String, Node)]) -> String {
+    let e = entries(n);
+    use Shell::*;
+    match sh {
+        Bash => bash(bin, &e),
+        Zsh => zsh(bin, &e),
+        Fish => fish(bin, &e),
+    }
+}
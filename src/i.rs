@@ -51,6 +51,39 @@ impl<'a> Parse<'a> for String {
     }
 }
 
+fn paths(i: &str, dirs: bool) -> Vec<String> {
+    let (pfx, d) = match i.rfind('/') {
+        Some(n) => (&i[..=n], &i[..=n]),
+        None => ("", "./"),
+    };
+    let Ok(r) = std::fs::read_dir(d) else {
+        return vec![];
+    };
+    r.filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let p = format!("{pfx}{}", e.file_name().to_string_lossy());
+            match e.path().is_dir() {
+                true => Some(format!("{p}/")),
+                false if dirs => None,
+                false => Some(p),
+            }
+        })
+        .filter(|p| p.starts_with(i))
+        .sorted()
+        .collect()
+}
+
+fn valid(
+    f: fn(&str) -> Result<PathBuf, String>,
+) -> impl Fn(&str) -> Result<Validation, CustomUserError> + Clone {
+    move |i| {
+        Ok(match f(i) {
+            Ok(_) => Validation::Valid,
+            Err(e) => Validation::Invalid(ErrorMessage::Custom(e)),
+        })
+    }
+}
+
 fn file_exist(i: &str) -> Result<PathBuf, String> {
     let p = PathBuf::from_str(i).map_err(|e| e.to_string())?;
     if !p.exists() {
@@ -77,6 +110,15 @@ impl<'a> Parse<'a> for FileExist {
     fn desc() -> &'static str {
         stringify!(FileExist)
     }
+    fn complete(i: &str) -> Vec<String> {
+        paths(i, false)
+    }
+    fn prompt(p: &str, d: Option<&str>) -> Result<String, InquireError> {
+        input_path(p)
+            .with_initial_value(d.unwrap_or(""))
+            .with_validator(valid(file_exist))
+            .prompt()
+    }
 }
 
 fn dir_exist(i: &str) -> Result<PathBuf, String> {
@@ -105,4 +147,13 @@ impl<'a> Parse<'a> for DirExist {
     fn desc() -> &'static str {
         stringify!(DirExist)
     }
+    fn complete(i: &str) -> Vec<String> {
+        paths(i, true)
+    }
+    fn prompt(p: &str, d: Option<&str>) -> Result<String, InquireError> {
+        input_path(p)
+            .with_initial_value(d.unwrap_or(""))
+            .with_validator(valid(dir_exist))
+            .prompt()
+    }
 }
@@ -1,5 +1,7 @@
+mod comp;
 mod i;
 mod parse;
+mod repl;
 mod util;
 
 mod com {
@@ -7,8 +9,8 @@ mod com {
     pub use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
     pub use inquire::{
         autocompletion::Replacement, list_option::ListOption, validator::CustomTypeValidator,
-        validator::ErrorMessage, Autocomplete, CustomType, CustomUserError, InquireError,
-        MultiSelect, Select, Text,
+        validator::ErrorMessage, validator::Validation, Autocomplete, Confirm, CustomType,
+        CustomUserError, InquireError, MultiSelect, Select, Text,
     };
     pub use itertools::Itertools;
     pub use std::{env::args, fmt::Display, path::PathBuf, str::FromStr};
@@ -16,32 +18,234 @@ mod com {
 
 pub use util::*;
 
+pub use comp::*;
 pub use i::*;
 pub use parse::*;
+pub use repl::*;
 
 use com::*;
 
 pub enum ActsErr<'a> {
-    Run(ParseCtx<'a>, String),
-    Inquire(String),
+    /// The error from `Args::output`.
+    Run(ParseCtx<'a>, RunErr),
+    Inquire(InquireError),
     ExpectedAct(ParseCtx<'a>, String),
     UnknownAct(ParseCtx<'a>, &'a str),
+    /// An abbreviation, its candidates and the usage of its node.
+    Ambiguous(ParseCtx<'a>, &'a str, Vec<&'static str>, String),
+    /// `UnknownAct` with the usage of its node, when it cannot be chosen at a prompt instead.
+    Unknown(ParseCtx<'a>, &'a str, Option<String>, String),
     Args(ParseCtx<'a>, ArgsParseErr<'a>, String),
+    /// `--help`, `-h` or `help` at a node, with its description, usage and `Acts::about`.
+    Help(ParseCtx<'a>, &'static str, String, String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct ParseCtx<'a> {
     pub pfx: Vec<Arg<'a>>,
+    pub mode: Mode,
+    /// Positional placeholders of the leaf, such as `<path>`.
+    pub pos: Vec<String>,
+    /// Option values keyed by act path, e.g. `{"headset": {"dis": {"path": "/dev/sde1"}}}`.
+    /// Read with `fs_read_json`, or `fs_read_toml` for `[headset.dis] path = "/dev/sde1"`.
+    pub cfg: Option<serde_json::Value>,
+    /// Whether the act was chosen at a prompt.
+    pub asked: bool,
+    /// File that the equivalent command of prompted runs is appended to.
+    pub history: Option<PathBuf>,
+    /// The program name, as given to `Acts::call`.
+    pub arg0: Arg<'a>,
+    /// Never prompt, e.g. from `--no-input`.
+    pub no_input: bool,
+    /// Usage of the options of the current node, from `Acts::add_opts`.
+    pub opts: String,
+    /// Options given to the nodes on the way, for the leaf.
+    pub inherit: NodeOpts<'a>,
+    /// Usage of the global options, from `Args::globals`.
+    pub globals: String,
+    /// Accept unique prefixes of acts and options, from `Acts::abbrev`.
+    pub abbrev: bool,
+    /// Where strings made while parsing are kept, set by `Acts::call_with`. `Args::next` leaks them without it.
+    pub own: Option<&'a Own>,
+}
+impl<'a> ParseCtx<'a> {
+    /// The section of `cfg` for the current path.
+    pub fn section(&self) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        self.pfx
+            .iter()
+            .try_fold(self.cfg.as_ref()?, |v, k| v.get(k))?
+            .as_object()
+    }
+    /// Whether missing input may be asked for.
+    pub fn prompts(&self) -> bool {
+        !self.no_input && interactive()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum Mode {
+    #[default]
+    Run,
+    /// Run, keeping the result of the leaf from `Args::output`, as `Acts::call` does.
+    Call(serde_json::Value),
+    /// Walk the path without running anything, recording each node on the way.
+    Usage(Vec<(String, Node)>),
+    /// Walk the path and record completion candidates for its last word.
+    Complete(Vec<String>),
+    /// Walk the path and record problems in the definition of its leaf.
+    Check(Vec<String>),
+}
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// The acts shown and the usage table of the node's own options.
+    Acts(Vec<[&'static str; 2]>, Vec<[String; 4]>),
+    Args(Vec<[String; 4]>),
+}
+
+/// Another name for an act, declared through `Acts::aliases`.
+#[derive(Clone, Debug)]
+pub struct Alias {
+    pub k: &'static str,
+    /// The act it stands for.
+    pub act: &'static str,
+    /// Print a warning pointing at `act` when used.
+    pub deprecated: bool,
+}
+impl Alias {
+    pub fn new(k: &'static str, act: &'static str) -> Self {
+        Self {
+            k,
+            act,
+            deprecated: false,
+        }
+    }
+}
+
+/// Longer help for an act or option, shown in its own `--help` but not in tables.
+#[derive(Clone, Debug, Default)]
+pub struct About {
+    /// Paragraphs that go on from the one line description.
+    pub long: &'static str,
+    /// Command lines, each with what it does, e.g. `["salt headset con --force", "Reconnect."]`.
+    pub examples: Vec<[&'static str; 2]>,
+    /// Related acts or options, e.g. `"headset dis"`.
+    pub see: Vec<&'static str>,
+}
+impl About {
+    /// The sections that are set, wrapped to `w` columns and indented by `i`.
+    pub fn render(&self, w: usize, i: usize) -> String {
+        let p = " ".repeat(i);
+        let mut r = vec![];
+        if !self.long.is_empty() {
+            r.push(wrap(self.long, w, i));
+        }
+        if !self.examples.is_empty() {
+            let e = self
+                .examples
+                .iter()
+                .map(|[c, d]| match d.is_empty() {
+                    true => format!("{p}  {c}"),
+                    false => format!("{p}  {c}\n{}", wrap(d, w, i + 6)),
+                })
+                .join("\n");
+            r.push(format!("{p}Examples:\n{e}"));
+        }
+        if !self.see.is_empty() {
+            r.push(wrap(&format!("See also: {}", self.see.join(", ")), w, i));
+        }
+        r.join("\n\n")
+    }
 }
 
 impl<'a> ActsErr<'a> {
-    fn display(self, arg0: &'a Arg) -> DActsErr<'a> {
-        DActsErr { e: self, arg0 }
+    /// 0 for help, 2 for usage errors, 130 when a prompt is interrupted,
+    /// 1 for other failures, or the code of the `RunErr` when `run` fails.
+    pub fn code(&self) -> i32 {
+        use ActsErr::*;
+        match *self {
+            Run(_, ref e) => e.code,
+            Inquire(ref e) => inquire_code(e),
+            Help(_, _, _, _) | Args(_, ArgsParseErr::Help(_, _), _) => 0,
+            Args(_, ArgsParseErr::Arg(_, ArgParseErr::Inquire(ref e), _), _) => inquire_code(e),
+            _ => 2,
+        }
+    }
+    pub fn kind(&self) -> ExitKind {
+        use ActsErr::*;
+        match *self {
+            Run(_, _) => ExitKind::Run,
+            Inquire(_) | Args(_, ArgsParseErr::Arg(_, ArgParseErr::Inquire(_), _), _) => {
+                ExitKind::Inquire
+            }
+            ExpectedAct(_, _) => ExitKind::ExpectedAct,
+            UnknownAct(_, _) | Unknown(_, _, _, _) => ExitKind::UnknownAct,
+            Ambiguous(_, _, _, _) => ExitKind::Ambiguous,
+            Help(_, _, _, _) | Args(_, ArgsParseErr::Help(_, _), _) => ExitKind::Help,
+            Args(_, _, _) => ExitKind::Args,
+        }
     }
+    fn display(self, arg0: Arg) -> Exit {
+        Exit {
+            code: self.code(),
+            kind: self.kind(),
+            msg: Usage { e: &self, arg0 }.to_string(),
+        }
+    }
+}
+/// What an `Exit` is for, as a kind of `ActsErr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitKind {
+    /// `Args::output` failed.
+    Run,
+    /// A prompt failed or was interrupted.
+    Inquire,
+    /// No act was given and none could be chosen at a prompt.
+    ExpectedAct,
+    UnknownAct,
+    /// An abbreviation matched more than one act.
+    Ambiguous,
+    /// The options of a leaf or node did not parse.
+    Args,
+    /// `--help` was asked for, at a node or a leaf.
+    Help,
 }
-struct DActsErr<'a> {
-    e: ActsErr<'a>,
-    arg0: Arg<'a>,
+/// A run that did not succeed, with the error and usage to print and the exit code.
+pub struct Exit {
+    msg: String,
+    code: i32,
+    kind: ExitKind,
+}
+impl Exit {
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+    pub fn kind(&self) -> ExitKind {
+        self.kind
+    }
+    /// Prints the message, to stdout for help and to stderr otherwise, and exits with `code`.
+    pub fn exit(self) -> ! {
+        match self.code {
+            0 => println!("{self}"),
+            _ => eprintln!("{self}"),
+        }
+        std::process::exit(self.code)
+    }
+}
+impl Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+fn inquire_code(e: &InquireError) -> i32 {
+    match e {
+        InquireError::OperationCanceled | InquireError::OperationInterrupted => 130,
+        _ => 1,
+    }
+}
+impl std::fmt::Debug for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
 }
 
 impl<'a> Display for ActsErr<'a> {
@@ -51,67 +255,436 @@ impl<'a> Display for ActsErr<'a> {
             Run(_, ref e) => write!(f, "Failed to run:\n{e}"),
             Inquire(ref e) => write!(f, "{e}"),
             ExpectedAct(_, _) => write!(f, "Expected an act.\n"),
+            Help(_, d, _, _) => writeln!(f, "{d}"),
             UnknownAct(_, ref e) => write!(f, "Unknown act '{e}.'\n"),
+            Ambiguous(_, ref e, ref m, _) => writeln!(
+                f,
+                "Ambiguous act '{e}', could be {}.",
+                m.iter().map(|m| format!("'{m}'")).join(", ")
+            ),
+            Unknown(_, ref e, ref m, _) => {
+                writeln!(f, "Unknown act '{e}.'")?;
+                if let Some(m) = m {
+                    writeln!(f, "Did you mean '{m}'?")?;
+                }
+                Ok(())
+            }
             Args(_, ref e, _) => match e {
-                ArgsParseErr::Help(_) => write!(f, "{e}"),
+                ArgsParseErr::Help(_, _) => writeln!(f, "{e}"),
                 _ => write!(f, "Failed to parse opts.\n{e}\n"),
             },
         }
     }
 }
-impl<'a> Display for DActsErr<'a> {
+/// An `ActsErr` followed by the usage that goes with it.
+struct Usage<'e, 'a> {
+    e: &'e ActsErr<'a>,
+    /// The program name.
+    arg0: Arg<'e>,
+}
+impl<'e, 'a> Display for Usage<'e, 'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use ActsErr::*;
         write!(f, "{}", self.e)?;
-        match self.e {
+        let act = |f: &mut std::fmt::Formatter<'_>, c: &ParseCtx, u: &str| {
+            let o = if c.opts.is_empty() { "" } else { " [opts...]" };
+            write!(
+                f,
+                "Usage: {}{o} <action>\n{u}",
+                [self.arg0].iter().chain(&c.pfx).join(" ")
+            )?;
+            if !c.opts.is_empty() {
+                write!(f, "\nOpts:\n{}", c.opts)?;
+            }
+            Ok(())
+        };
+        let c = match *self.e {
             ExpectedAct(ref c, ref u) => {
-                write!(f, "Usage: {} {} <action>\n{u}", self.arg0, c.pfx.join(" "))?;
+                act(f, c, u)?;
+                c
             }
-            UnknownAct(ref c, ref u) => {
-                write!(f, "Usage: {} {} <action>\n{u}", self.arg0, c.pfx.join(" "))?;
+            UnknownAct(ref c, _) => {
+                write!(
+                    f,
+                    "Usage: {} <action>",
+                    [self.arg0].iter().chain(&c.pfx).join(" ")
+                )?;
+                c
+            }
+            Unknown(ref c, _, _, ref u) | Ambiguous(ref c, _, _, ref u) => {
+                act(f, c, u)?;
+                c
+            }
+            Help(ref c, _, ref u, ref a) => {
+                act(f, c, u)?;
+                if !a.is_empty() {
+                    write!(f, "\n\n{a}")?;
+                }
+                c
             }
-            Args(ref c, _, ref u) => {
+            Args(ref c, ref e, ref u) => {
                 write!(
                     f,
-                    "Usage: {} {} <opts...>\nOpts:\n{u}",
-                    self.arg0,
-                    c.pfx.join(" ")
+                    "Usage: {} <opts...>\nOpts:\n{u}",
+                    [self.arg0]
+                        .iter()
+                        .chain(&c.pfx)
+                        .copied()
+                        .chain(c.pos.iter().map(|p| p.as_str()))
+                        .join(" ")
                 )?;
+                match e {
+                    ArgsParseErr::Help(_, a) if !a.is_empty() => write!(f, "\n\n{a}")?,
+                    _ => (),
+                }
+                c
             }
-            _ => (),
+            _ => return Ok(()),
         };
+        if !c.globals.is_empty() {
+            write!(f, "\nGlobal opts:\n{}", c.globals)?;
+        }
         Ok(())
     }
 }
 impl<'a> From<InquireError> for ActsErr<'a> {
     fn from(v: InquireError) -> Self {
-        Self::Inquire(format!("{v}"))
+        Self::Inquire(v)
     }
 }
 
 pub type Arg<'a> = &'a str;
+pub const COMPLETE: &str = "__complete";
+pub const NO_INPUT: &str = "--no-input";
+/// Whether `NO_INPUT` is given before `--`, and `args` without it.
+fn no_input<'a>(args: &[Arg<'a>]) -> (bool, Vec<Arg<'a>>) {
+    let e = args.iter().position(|&a| a == PFX).unwrap_or(args.len());
+    let r = args[..e]
+        .iter()
+        .filter(|&&a| a != NO_INPUT)
+        .chain(&args[e..])
+        .copied()
+        .collect_vec();
+    (r.len() < args.len(), r)
+}
+/// A node of the act tree, usually from `#[derive(Acts)]`.
+/// The derive only writes the required methods and takes no attributes for the provided ones,
+/// so overriding `about`, `add_opts`, `aliases`, `hidden` or `abbrev` needs a hand-written impl.
 pub trait Acts<C>: Sized {
-    fn run(c: &C) -> Result<(), String> {
-        let args = args().collect_vec();
-        let a: Vec<_> = args.iter().map(|e| e.as_str()).collect();
-        let mut s = ParseCtx { pfx: vec![] };
-        Self::next(c, &mut s, &a[1..]).map_err(|e| format!("{}", e.display(&a[0])))
+    /// Runs the act named by the process arguments and returns its result.
+    fn run(c: &C) -> Result<serde_json::Value, Exit> {
+        Self::run_with(c, ParseCtx::default())
+    }
+    /// Like `run`, starting from `s`, e.g. to set `cfg`.
+    fn run_with(c: &C, s: ParseCtx) -> Result<serde_json::Value, Exit> {
+        let a: Vec<String> = args().collect();
+        let a = a.iter().map(|a| a.as_str()).collect_vec();
+        Self::run_from(
+            c,
+            s,
+            a.first().unwrap_or(&""),
+            a.get(1..).unwrap_or_default(),
+        )
+    }
+    /// Like `run_with`, for the words after the program name `arg0` instead of the process arguments,
+    /// e.g. the rest from `Args::globals`. Unlike `call_with`, it answers `COMPLETE` for the shell.
+    fn run_from<'a>(
+        c: &C,
+        s: ParseCtx<'a>,
+        arg0: Arg<'a>,
+        args: &[Arg<'a>],
+    ) -> Result<serde_json::Value, Exit> {
+        if args.first() == Some(&COMPLETE) {
+            for r in Self::complete(c, &args[1..]) {
+                println!("{r}");
+            }
+            return Ok(serde_json::Value::Null);
+        }
+        Self::call_with(c, s, arg0, args)
+    }
+    /// Runs the act named by `args`, the words after the program name `arg0`, and returns its result.
+    /// For embedding, tests, and multi-call binaries that pick a tree by `arg0`.
+    /// Never prompts; missing input is an error. Use `call_with` to allow prompts.
+    fn call<'a>(c: &C, arg0: Arg<'a>, args: &[Arg<'a>]) -> Result<serde_json::Value, Exit> {
+        let s = ParseCtx {
+            no_input: true,
+            ..Default::default()
+        };
+        Self::call_with(c, s, arg0, args)
+    }
+    /// Like `call`, starting from `s`, which prompts at a terminal unless `s.no_input` is set.
+    fn call_with<'a>(
+        c: &C,
+        s: ParseCtx<'a>,
+        arg0: Arg<'a>,
+        args: &[Arg<'a>],
+    ) -> Result<serde_json::Value, Exit> {
+        let o = Own::default();
+        let mut s = ParseCtx {
+            arg0,
+            own: Some(&o),
+            mode: Mode::Call(serde_json::Value::Null),
+            ..s
+        };
+        let (n, r) = no_input(args);
+        s.no_input |= n;
+        Self::next(c, &mut s, &r).map_err(|e| e.display(arg0))?;
+        match s.mode {
+            Mode::Call(v) => Ok(v),
+            _ => Ok(serde_json::Value::Null),
+        }
+    }
+    /// Reads lines like `headset dis --dev x` and runs them until `exit`, so that `c` is built once.
+    fn repl(c: &C) {
+        Self::repl_with(c, ParseCtx::default())
+    }
+    /// Like `repl`, starting each line from `s`.
+    fn repl_with(c: &C, s: ParseCtx) {
+        let arg0 = bin();
+        let mut h: Vec<String> = vec![];
+        loop {
+            let l = Text::new(&format!("{arg0}>"))
+                .with_autocomplete(Line {
+                    c,
+                    f: Self::complete,
+                    h: h.clone(),
+                })
+                .prompt();
+            let Ok(l) = l else {
+                break;
+            };
+            let w = match words(&l) {
+                Ok(w) => w,
+                Err(e) => {
+                    eprintln!("{e}");
+                    continue;
+                }
+            };
+            if w.is_empty() {
+                continue;
+            }
+            h.push(l.trim().to_owned());
+            let mut a = w.iter().map(|w| w.as_str()).collect_vec();
+            // Acts of the same name come first, as with `help` in `next`.
+            let b = BUILTINS
+                .into_iter()
+                .filter(|b| !Self::opts().contains(b))
+                .collect_vec();
+            match a[0] {
+                k if !b.contains(&k) => (),
+                "exit" => break,
+                "history" => {
+                    for (i, l) in h.iter().enumerate() {
+                        println!("{i:>4} {l}");
+                    }
+                    continue;
+                }
+                "help" if a.len() == 1 => {
+                    println!("{}", Self::usage());
+                    println!("Also: {}", b.join(", "));
+                    continue;
+                }
+                "help" => {
+                    a.remove(0);
+                    a.push("--help");
+                }
+                _ => (),
+            }
+            match Self::call_with(c, s.clone(), &arg0, &a) {
+                Err(e) if e.code() == 0 => println!("{e}"),
+                Err(e) => eprintln!("{e}"),
+                Ok(_) => (),
+            }
+        }
+    }
+    /// Runs and exits with `Exit::code`, printing failures to stderr. Meant to be all of `main`.
+    fn main(c: &C) -> ! {
+        Self::main_with(c, ParseCtx::default())
+    }
+    /// Like `main`, starting from `s`.
+    fn main_with(c: &C, s: ParseCtx) -> ! {
+        let a: Vec<String> = args().collect();
+        let a = a.iter().map(|a| a.as_str()).collect_vec();
+        Self::main_from(
+            c,
+            s,
+            a.first().unwrap_or(&""),
+            a.get(1..).unwrap_or_default(),
+        )
+    }
+    /// Like `main_with`, for the words after the program name `arg0`, like `run_from`.
+    fn main_from<'a>(c: &C, s: ParseCtx<'a>, arg0: Arg<'a>, args: &[Arg<'a>]) -> ! {
+        match Self::run_from(c, s, arg0, args) {
+            Ok(_) => std::process::exit(0),
+            Err(e) => e.exit(),
+        }
     }
 
     fn next<'a>(c: &C, s: &mut ParseCtx<'a>, args: &[Arg<'a>]) -> Result<(), ActsErr<'a>> {
+        let mut u: Vec<[String; 4]> = vec![];
+        Self::add_opts(c, &mut u);
+        s.opts = match u.is_empty() {
+            true => String::new(),
+            false => to_table(&u),
+        };
+        if let Mode::Usage(ref mut n) = s.mode {
+            n.push((s.pfx.join(" "), Node::Acts(Self::shown(), u.clone())));
+        }
+        // The options of this node are completed up to its first act, and their values are left alone.
+        if let Mode::Complete(ref mut r) = s.mode {
+            if let Some((i, h)) = args.split_last() {
+                match leading(h, &u) {
+                    Ok((_, n)) if n == h.len() && i.starts_with('-') => {
+                        *r = node_keys(&u)
+                            .into_iter()
+                            .map(|[k, _]| k)
+                            .filter(|k| k.starts_with(i))
+                            .collect();
+                        return Ok(());
+                    }
+                    Ok((_, n)) if n > h.len() => return Ok(()),
+                    Err(_) => return Ok(()),
+                    Ok(_) => (),
+                }
+            }
+        }
+        let args = match leading(args, &u) {
+            Ok((o, n)) => {
+                s.inherit.extend(o);
+                &args[n..]
+            }
+            Err(_) if matches!(s.mode, Mode::Complete(_)) => return Ok(()),
+            Err("--help" | "-h") => {
+                let a = Self::about().render(columns(), 0);
+                return Err(ActsErr::Help(
+                    s.to_owned(),
+                    Self::desc_act(),
+                    Self::usage(),
+                    a,
+                ));
+            }
+            Err(_) if u.is_empty() => args,
+            Err(a) => {
+                let m = keys(&u).into_iter().map(|[k, _]| k).collect_vec();
+                let m = suggest(a, &m)
+                    .map(|m| vec![[a.to_owned(), m.to_owned()]])
+                    .unwrap_or_default();
+                let e = ArgsParseErr::UnknownArgs(vec![a], m, s.opts.to_owned());
+                return Err(ActsErr::Args(s.to_owned(), e, s.opts.to_owned()));
+            }
+        };
+        if let Mode::Check(ref mut r) = s.mode {
+            let p = s.pfx.join(" ");
+            let o = Self::opts();
+            for l in Self::aliases() {
+                if !o.contains(&l.act) {
+                    r.push(format!(
+                        "{p}: Alias '{}' is for unknown act '{}'.",
+                        l.k, l.act
+                    ));
+                }
+                if o.contains(&l.k) {
+                    r.push(format!("{p}: Alias '{}' is also an act.", l.k));
+                }
+            }
+            for h in Self::hidden() {
+                if !o.contains(&h) {
+                    r.push(format!("{p}: Hidden act '{h}' is unknown."));
+                }
+            }
+            let l = Self::paths()
+                .into_iter()
+                .flat_map(|a| {
+                    let mut s = ParseCtx {
+                        mode: Mode::Usage(vec![]),
+                        ..Default::default()
+                    };
+                    let _ = Self::next(c, &mut s, &a);
+                    match s.mode {
+                        Mode::Usage(n) => n,
+                        _ => vec![],
+                    }
+                })
+                .filter_map(|(_, n)| match n {
+                    Node::Args(a) => Some(a),
+                    Node::Acts(..) => None,
+                })
+                .flatten()
+                .collect_vec();
+            for u in u.iter().filter(|u| !l.iter().any(|l| l[0] == u[0])) {
+                r.push(format!(
+                    "{p}: Option '{}' is not read by any act below.",
+                    u[0]
+                ));
+            }
+        }
+        if let Mode::Usage(_) | Mode::Check(_) = s.mode {
+            if args.is_empty() {
+                return Ok(());
+            }
+            return Self::next_impl(c, s, &Self::canonical(args[0]), &args[1..]);
+        }
+        if let Mode::Complete(ref mut r) = s.mode {
+            if args.len() <= 1 {
+                let i = args.first().unwrap_or(&"");
+                *r = Self::shown()
+                    .into_iter()
+                    .map(|[o, _]| o)
+                    .filter(|o| o.starts_with(i))
+                    .map(|o| o.to_owned())
+                    .collect();
+                return Ok(());
+            }
+            return match Self::next_impl(c, s, &Self::canonical(args[0]), &args[1..]) {
+                Err(ActsErr::UnknownAct(_, _)) => Ok(()),
+                e => e,
+            };
+        }
         if args.is_empty() {
+            if !s.prompts() {
+                return Err(ActsErr::ExpectedAct(s.to_owned(), Self::usage()));
+            }
             print!("{}", ActsErr::ExpectedAct(s.to_owned(), Self::usage()));
-            return Self::next(c, s, &[Self::select_act()?]);
+            let a = Self::select_act()?;
+            s.asked = true;
+            return Self::next(c, s, &[a]);
+        };
+        if args[0] == "help" && !Self::opts().contains(&"help") {
+            let mut a = args[1..].to_vec();
+            a.push("--help");
+            return Self::next(c, s, &a);
+        }
+        s.abbrev |= Self::abbrev();
+        let l = Self::aliases().into_iter().find(|l| l.k == args[0]);
+        let a = match l {
+            Some(l) => {
+                if l.deprecated {
+                    eprintln!("Warning: '{}' is deprecated, use '{}' instead.", l.k, l.act);
+                }
+                l.act
+            }
+            None if s.abbrev => match unique_prefix(args[0], &Self::visible()) {
+                Ok(o) => o.unwrap_or(args[0]),
+                Err(m) => return Err(ActsErr::Ambiguous(s.to_owned(), args[0], m, Self::usage())),
+            },
+            None => args[0],
         };
-        let a = &args[0];
+        let a = &a;
         let args = &args[1..];
 
         use ActsErr::*;
         match Self::next_impl(c, s, a, args) {
             Err(e) => match e {
-                UnknownAct(_, _) => {
+                UnknownAct(p, a) => {
+                    let m = suggest(a, &Self::visible()).map(|m| m.to_owned());
+                    let e = Unknown(p, a, m, Self::usage());
+                    if !s.prompts() {
+                        return Err(e);
+                    }
                     print!("{e}");
-                    Self::next(c, s, &[Self::select_act()?])
+                    let a = Self::select_act()?;
+                    s.asked = true;
+                    Self::next(c, s, &[a])
                 }
                 _ => return Err(e),
             },
@@ -119,25 +692,147 @@ pub trait Acts<C>: Sized {
         }
     }
     fn select_act<'a>() -> Result<&'static str, ActsErr<'a>> {
-        let opts: Vec<_> = to_lines(&Self::usage_v())
+        let v = Self::shown();
+        let opts: Vec<_> = to_lines(&v)
             .into_iter()
             .enumerate()
             .map(|(i, o)| ListOption::new(i, o))
             .collect();
-        Ok(Self::opts()[Select::new("Choose an action.", opts)
+        Ok(v[Select::new("Choose an action.", opts)
             .with_page_size(50)
             .prompt()?
-            .index])
+            .index][0])
     }
 
-    fn list<'a>() -> Vec<Vec<Arg<'a>>> {
+    /// Paths to every leaf, except through hidden acts at any level.
+    fn list<'a>(c: &C) -> Vec<Vec<Arg<'a>>> {
+        Self::paths()
+            .into_iter()
+            .filter(|p| {
+                let mut s = ParseCtx {
+                    mode: Mode::Usage(vec![]),
+                    ..Default::default()
+                };
+                let _ = Self::next(c, &mut s, p);
+                let Mode::Usage(n) = s.mode else {
+                    return false;
+                };
+                // Each act on the way has to be shown by the node above it.
+                (0..p.len()).all(|i| {
+                    let k = p[..i].join(" ");
+                    n.iter().any(|(e, n)| match n {
+                        Node::Acts(a, _) => *e == k && a.iter().any(|[a, _]| *a == p[i]),
+                        Node::Args(_) => false,
+                    })
+                })
+            })
+            .collect()
+    }
+    /// Paths to every leaf, hidden or not.
+    fn paths<'a>() -> Vec<Vec<Arg<'a>>> {
         let pfx = vec![];
         let mut res: Vec<Vec<Arg<'a>>> = vec![];
         Self::add_paths(&pfx, &mut res);
         return res;
     }
     fn usage() -> String {
-        to_table(&Self::usage_v())
+        to_table(&Self::shown())
+    }
+    /// `usage_v` without the hidden acts.
+    fn shown() -> Vec<[&'static str; 2]> {
+        let h = Self::hidden();
+        Self::usage_v()
+            .into_iter()
+            .filter(|[k, _]| !h.contains(k))
+            .collect()
+    }
+    /// `opts` without the hidden acts.
+    fn visible() -> Vec<&'static str> {
+        let h = Self::hidden();
+        Self::opts()
+            .into_iter()
+            .filter(|o| !h.contains(o))
+            .collect()
+    }
+    /// The act named by `a`, following aliases.
+    fn canonical<'a>(a: Arg<'a>) -> Arg<'a> {
+        match Self::aliases().into_iter().find(|l| l.k == a) {
+            Some(l) => l.act,
+            None => a,
+        }
+    }
+    fn nodes(c: &C) -> Vec<(String, Node)> {
+        let mut r: Vec<(String, Node)> = vec![];
+        for p in Self::list(c) {
+            let mut s = ParseCtx {
+                mode: Mode::Usage(vec![]),
+                ..Default::default()
+            };
+            let _ = Self::next(c, &mut s, &p);
+            if let Mode::Usage(n) = s.mode {
+                for (k, v) in n {
+                    if !r.iter().any(|(e, _)| *e == k) {
+                        r.push((k, v));
+                    }
+                }
+            }
+        }
+        r
+    }
+    /// Candidates for the last of `args`, which are the words after the program name.
+    fn complete(c: &C, args: &[Arg]) -> Vec<String> {
+        let mut s = ParseCtx {
+            mode: Mode::Complete(vec![]),
+            ..Default::default()
+        };
+        let _ = Self::next(c, &mut s, &no_input(args).1);
+        match s.mode {
+            Mode::Complete(r) => r,
+            _ => vec![],
+        }
+    }
+    fn completion(c: &C, sh: Shell, bin: &str) -> String {
+        script(sh, bin, &Self::nodes(c))
+    }
+    /// Validates every leaf with `Args::check`. Meant for tests and debug builds.
+    fn check(c: &C) -> Result<(), String> {
+        let mut r = vec![];
+        for p in Self::paths() {
+            let mut s = ParseCtx {
+                mode: Mode::Check(vec![]),
+                ..Default::default()
+            };
+            let _ = Self::next(c, &mut s, &p);
+            if let Mode::Check(e) = s.mode {
+                r.extend(e);
+            }
+        }
+        if r.is_empty() {
+            Ok(())
+        } else {
+            Err(r.into_iter().unique().join("\n"))
+        }
+    }
+
+    /// Longer help for this node, shown in its own `--help` only.
+    fn about() -> About {
+        About::default()
+    }
+    /// Options of this node, given between its name and the next act, as usage rows like `Args::add_usage`.
+    /// They are passed down to the leaf, which must declare them too, unless the leaf is given them itself.
+    fn add_opts(_c: &C, _r: &mut Vec<[String; 4]>) {}
+    /// Other names for acts, e.g. `Alias::new("rm", "remove")`.
+    fn aliases() -> Vec<Alias> {
+        vec![]
+    }
+    /// Acts that work but are left out of usage, prompts, `list` and completion.
+    fn hidden() -> Vec<&'static str> {
+        vec![]
+    }
+    /// Whether acts and options below this node may be given by a unique prefix, e.g. `salt head dis`.
+    /// Off by default, since adding an act can make an abbreviation in use ambiguous.
+    fn abbrev() -> bool {
+        false
     }
 
     fn opts() -> Vec<&'static str>;
@@ -151,49 +846,392 @@ pub trait Acts<C>: Sized {
     fn usage_v() -> Vec<[&'static str; 2]>;
     fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>);
 }
+/// A leaf of the act tree, usually from `#[derive(Args)]`.
+/// The derive only writes the required methods and takes no attributes for the provided ones,
+/// so overriding `decls`, `about` or `output` needs a hand-written impl.
 pub trait Args<C>: Sized {
-    fn next_impl<'a>(c: &C, args: &[Arg<'a>]) -> Result<(), ArgsErr<'a>> {
-        let mut args = ParsedArgs::new(args).map_err(|e| {
+    fn next_impl<'a>(
+        c: &C,
+        s: &ParseCtx<'a>,
+        args: &[Arg<'a>],
+    ) -> Result<serde_json::Value, ArgsErr<'a>> {
+        let mut u: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut u);
+        let d = Self::decls();
+        let o = s.own.unwrap_or_else(|| Box::leak(Box::default()));
+        let err = |e| {
             use ParsedArgsErr::*;
             match e {
                 UnexpectedToken(a) => ArgsParseErr::UnexpectedToken(a, Self::usage(c)),
+                Ambiguous(a, m) => ArgsParseErr::Ambiguous(a, m, Self::usage(c)),
             }
-        })?;
+        };
+        let args = match s.abbrev {
+            true => unabbrev(args, &keys(&u).into_iter().map(|[k, _]| k).collect_vec(), o)
+                .map_err(err)?,
+            false => args.to_vec(),
+        };
+        let args = positional(
+            expand(&args, &Self::shorts(c), &u),
+            &d.iter().filter(|d| d.pos).map(|d| d.k).collect_vec(),
+            &u,
+        )
+        .map_err(err)?;
+        let args =
+            inherit(args, &s.inherit, &u).map_err(|n| ArgsParseErr::NotRead(n, Self::usage(c)))?;
+        let w = args.clone();
+        let args = &config(
+            env(
+                args,
+                &d.iter().filter_map(|d| Some((d.k, d.env?))).collect_vec(),
+                &u,
+                o,
+            ),
+            s.section(),
+            &u,
+            o,
+        );
+        let (args, v) = &unmark(args);
+        let mut args = ParsedArgs::with_values(args, v).map_err(err)?;
+        args.ask = s.prompts();
+        args.own = Some(o);
+        args.last = Self::decls()
+            .into_iter()
+            .filter(|d| d.last)
+            .map(|d| d.k)
+            .collect();
 
         if args.consume(&format!("{PFX}help")).is_some() {
-            return Err(ArgsParseErr::Help(Self::usage(c)).into());
+            return Err(ArgsParseErr::Help(Self::desc_act().to_owned(), Self::help()).into());
+        }
+        if args.consume(&format!("{PFX}interactive")).is_some() {
+            if !s.prompts() {
+                return Err(ArgsParseErr::NoPrompts(Self::usage(c)).into());
+            }
+            args.form = Some(Form { u: u.clone() });
         }
 
-        let a = Self::new(c, &mut args)?;
-
-        let u = args
+        // Checked before `new`, which may prompt for the options that are missing.
+        let k = keys(&u).into_iter().map(|[k, _]| k).collect_vec();
+        let g = args
             .keys
             .iter()
-            .filter(|k| !k.used)
-            .filter(|k| args.args[k.i] != PFX)
             .map(|k| args.args[k.i])
-            .collect::<Vec<_>>();
-        if !u.is_empty() {
-            return Err(ArgsParseErr::UnknownArgs(u, Self::usage(c)).into());
+            .filter(|&a| a != PFX)
+            .collect_vec();
+        let n = g
+            .iter()
+            .copied()
+            .filter(|a| !k.iter().any(|k| k == a))
+            .collect_vec();
+        if !n.is_empty() {
+            let m = n
+                .iter()
+                .filter_map(|a| Some([a.to_string(), suggest(a, &k)?.to_owned()]))
+                .collect();
+            return Err(ArgsParseErr::UnknownArgs(n, m, Self::usage(c)).into());
+        }
+        if let Some(&k) = g
+            .iter()
+            .find(|&&a| u.iter().filter(|u| u[0] == a).count() > 1)
+        {
+            return Err(ArgsParseErr::Duplicate(k, Self::usage(c)).into());
         }
 
-        let _ = a.run(c).map_err(|s| ArgsErr::Run(s))?;
-        Ok(())
+        let a = Self::new(c, &mut args);
+        if let Some(k) = args.dup.first() {
+            return Err(ArgsParseErr::Duplicate(k, Self::usage(c)).into());
+        }
+        let a = a?;
+        if args.form.is_some() {
+            println!("{}", to_table(&args.answers));
+            if !Confirm::new("Run?").with_default(true).prompt()? {
+                Err(InquireError::OperationCanceled)?
+            }
+        }
+        if s.asked || !args.answers.is_empty() {
+            // Built from the options given, not the values from the environment or config, which may be secrets.
+            let a = match args.form {
+                Some(_) => vec![],
+                None => to_words(&w),
+            };
+            let l = [base(s.arg0).to_owned()]
+                .into_iter()
+                .chain(s.pfx.iter().map(|a| a.to_string()))
+                .chain(a)
+                .chain(args.cmd)
+                .map(|a| sh_quote(&a))
+                .join(" ");
+            println!("{l}");
+            if let Some(ref h) = s.history {
+                fs_append(h, format!("{l}\n")).map_err(|e| ArgsErr::Run(String::from(e).into()))?;
+            }
+        }
+
+        a.output(c).map_err(ArgsErr::Run)
     }
     fn next<'a>(c: &C, s: &mut ParseCtx<'a>, args: &[Arg<'a>]) -> Result<(), ActsErr<'a>> {
-        match Self::next_impl(c, args) {
-            Err(e) => match e {
-                ArgsErr::Run(r) => Err(ActsErr::Run(s.to_owned(), r)),
-                ArgsErr::Parse(e) => Err(ActsErr::Args(s.to_owned(), e, Self::usage(c))),
-            },
-            Ok(o) => Ok(o),
+        if let Mode::Usage(ref mut n) = s.mode {
+            let mut r = vec![];
+            Self::add_usage(c, &mut r);
+            n.push((s.pfx.join(" "), Node::Args(r)));
+            return Ok(());
+        }
+        if let Mode::Complete(ref mut r) = s.mode {
+            *r = Self::complete(c, args);
+            return Ok(());
+        }
+        if let Mode::Check(ref mut r) = s.mode {
+            let p = s.pfx.join(" ");
+            r.extend(Self::check(c).into_iter().map(|e| format!("{p}: {e}")));
+            return Ok(());
+        }
+        s.pos = Self::synopsis(c);
+        let v = Self::next_impl(c, s, args).map_err(|e| match e {
+            ArgsErr::Run(r) => ActsErr::Run(s.to_owned(), r),
+            ArgsErr::Inquire(e) => ActsErr::Inquire(e),
+            ArgsErr::Parse(e) => ActsErr::Args(s.to_owned(), e, Self::usage_in(c, s)),
+        })?;
+        if let Mode::Call(ref mut r) = s.mode {
+            *r = v;
         }
+        Ok(())
     }
     fn usage(c: &C) -> String {
+        Self::usage_in(c, &ParseCtx::default())
+    }
+    /// The usage table, with the value each option falls back to: its environment variable when set,
+    /// else its entry in the config file of `s`, else its default, which is listed with the variable to set.
+    fn usage_in(c: &C, s: &ParseCtx) -> String {
         let mut r: Vec<[String; 4]> = vec![];
         Self::add_usage(c, &mut r);
+        let e = Self::decls()
+            .into_iter()
+            .filter_map(|d| Some((d.k, d.env?)))
+            .collect_vec();
+        for r in r.iter_mut() {
+            let e = e
+                .iter()
+                .find(|(k, _)| *k == r[0])
+                .map(|&(_, e)| (e, env_var(e)));
+            let o = s
+                .section()
+                .and_then(|o| o.get(r[0].trim_start_matches(PFX)));
+            r[3] = match (e, o) {
+                (Some((e, Ok(v))), _) => format!(" (env: {e}={v})"),
+                (_, Some(v)) => format!(" (config: {})", values(v).join(", ")),
+                (Some((e, Err(_))), None) => format!("{} (env: {e})", r[3]),
+                (None, None) => continue,
+            };
+        }
+        for s in Self::shorts(c) {
+            if let Some(r) = r.iter_mut().find(|r| r[0] == s.k) {
+                r[0] = format!("-{}, {}", s.c, s.k);
+            }
+        }
         to_table(&r)
     }
+    /// Problems in the definition that would otherwise only show up while parsing.
+    fn check(c: &C) -> Vec<String> {
+        let mut u: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut u);
+        let d = Self::decls();
+        let mut r = vec![];
+        let k = u.iter().map(|u| u[0].to_owned()).collect_vec();
+        let b = [format!("{PFX}help"), format!("{PFX}interactive")];
+        for (k, n) in k.iter().chain(b.iter()).counts() {
+            if n > 1 {
+                r.push(format!("Option '{k}' is declared more than once."));
+            }
+        }
+        for u in u.iter().filter(|u| !takes_value(&u[1])) {
+            if k.contains(&neg(&u[0])) {
+                r.push(format!(
+                    "Option '{}' clashes with the negation of '{}'.",
+                    neg(&u[0]),
+                    u[0]
+                ));
+            }
+        }
+        for u in u
+            .iter()
+            .filter(|u| u[0] == NO_INPUT || (!takes_value(&u[1]) && neg(&u[0]) == NO_INPUT))
+        {
+            r.push(format!(
+                "Option '{}' clashes with '{NO_INPUT}', which turns off prompts.",
+                u[0]
+            ));
+        }
+        for (s, n) in d.iter().filter_map(|d| d.short).counts() {
+            if n > 1 {
+                r.push(format!("Short option '-{s}' is declared more than once."));
+            }
+        }
+        for d in d.iter() {
+            if !u.iter().any(|u| u[0] == d.k) {
+                r.push(format!("Declaration for unknown option '{}'.", d.k));
+            }
+        }
+        let p = d.iter().filter(|d| d.pos).collect_vec();
+        for d in p.iter().rev().skip(1) {
+            if u.iter().any(|u| u[0] == d.k && is_list(&u[1])) {
+                r.push(format!("Positional list '{}' is not the last one.", d.k));
+            }
+        }
+        r
+    }
+    /// Placeholders of the positional options, in order.
+    fn synopsis(c: &C) -> Vec<String> {
+        let mut r: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut r);
+        Self::decls()
+            .into_iter()
+            .filter(|d| d.pos)
+            .filter_map(|d| {
+                let u = r.iter().find(|r| r[0] == d.k)?;
+                let n = d.k.trim_start_matches(PFX);
+                Some(if is_list(&u[1]) {
+                    format!("[{n}...]")
+                } else if u[1].starts_with("Req<") {
+                    format!("<{n}>")
+                } else {
+                    format!("[{n}]")
+                })
+            })
+            .collect()
+    }
+    fn shorts(c: &C) -> Vec<Short> {
+        let mut r: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut r);
+        Self::decls()
+            .into_iter()
+            .filter_map(|d| {
+                Some(Short {
+                    c: d.short?,
+                    k: d.k,
+                    v: r.iter().any(|r| r[0] == d.k && takes_value(&r[1])),
+                })
+            })
+            .collect()
+    }
+    fn complete<'a>(c: &C, args: &[Arg<'a>]) -> Vec<String> {
+        let (i, args) = match args.split_last() {
+            Some((i, a)) => (*i, a),
+            None => ("", args),
+        };
+        let mut r: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut r);
+        // Options are offered for a word that starts like one, or in place of a value nothing takes.
+        let o = !args.contains(&PFX);
+        let opts = || match o {
+            true => keys(&r)
+                .into_iter()
+                .map(|[k, _]| k)
+                .filter(|k| k.starts_with(i))
+                .collect(),
+            false => vec![],
+        };
+        if o && i.starts_with(PFX) {
+            return opts();
+        }
+        let p = Self::decls()
+            .into_iter()
+            .filter(|d| d.pos)
+            .map(|d| d.k)
+            .collect_vec();
+        // The word being completed is parsed in place of `m`, to find the option it is a value of.
+        let m = "\u{1}";
+        let a = args.iter().copied().chain([m]).collect_vec();
+        let Ok(args) = positional(expand(&a, &Self::shorts(c), &r), &p, &r) else {
+            return opts();
+        };
+        let (args, v) = &unmark(&args);
+        let Ok(mut p) = ParsedArgs::with_values(args, v) else {
+            return vec![];
+        };
+        let Some(k) = args
+            .iter()
+            .position(|&a| a == m)
+            .and_then(|n| p.keys.iter().rev().find(|k| k.i < n))
+            .map(|k| args[k.i])
+        else {
+            return opts();
+        };
+        if let Some(f) = Self::decls()
+            .into_iter()
+            .find(|d| d.k == k)
+            .and_then(|d| d.comp)
+        {
+            return f(c).into_iter().filter(|e| e.starts_with(i)).collect();
+        }
+        p.comp = Some(Comp { k, i, r: vec![] });
+        let _ = Self::new(c, &mut p);
+        p.comp.map(|e| e.r).unwrap_or_default()
+    }
+    /// Takes the options of `Self` out of `args`, before or after any act, for options shared by a whole tree.
+    /// Returns them with a `ParseCtx` that lists them under "Global opts", and the rest of `args` for `Acts::main_from`.
+    /// Leaves never see these options, so a leaf option of the same name, or a value spelled like one, is taken too.
+    /// Their shorts must be given alone: `-v -q`, not `-vq`.
+    fn globals<'a>(
+        c: &C,
+        arg0: Arg<'a>,
+        args: &[Arg<'a>],
+    ) -> Result<(Self, ParseCtx<'a>, Vec<Arg<'a>>), Exit> {
+        let mut u: Vec<[String; 4]> = vec![];
+        Self::add_usage(c, &mut u);
+        let d = Self::decls();
+        let (g, r) = take_opts(args, &u, &Self::shorts(c));
+        let o = Own::default();
+        let g = env(
+            g,
+            &d.iter().filter_map(|d| Some((d.k, d.env?))).collect_vec(),
+            &u,
+            &o,
+        );
+        let e = |e| ActsErr::Args(ParseCtx::default(), e, Self::usage(c)).display(arg0);
+        let (g, v) = &unmark(&g);
+        let mut p = ParsedArgs::with_values(g, v).map_err(|e| match e {
+            ParsedArgsErr::UnexpectedToken(a) => ArgsParseErr::UnexpectedToken(a, Self::usage(c)),
+            ParsedArgsErr::Ambiguous(a, m) => ArgsParseErr::Ambiguous(a, m, Self::usage(c)),
+        });
+        let v = match p {
+            Ok(ref mut p) => {
+                p.own = Some(&o);
+                Self::new(c, p)
+            }
+            Err(e) => Err(e),
+        }
+        .map_err(e)?;
+        let s = ParseCtx {
+            globals: Self::usage(c),
+            ..Default::default()
+        };
+        Ok((v, s, r))
+    }
+    /// Longer help for this act, shown in its own `--help` only. Options have theirs in `Decl::about`.
+    fn about() -> About {
+        About::default()
+    }
+    /// `about` of the act and its options, wrapped to the terminal.
+    fn help() -> String {
+        let w = columns();
+        let o = Self::decls()
+            .into_iter()
+            .map(|d| (d.k, d.about.render(w, 4)))
+            .filter(|(_, a)| !a.is_empty())
+            .map(|(k, a)| format!("  {k}\n{a}"))
+            .join("\n\n");
+        let a = Self::about().render(w, 0);
+        match o.is_empty() {
+            true => a,
+            false if a.is_empty() => format!("Options:\n{o}"),
+            false => format!("{a}\n\nOptions:\n{o}"),
+        }
+    }
+    /// Per-option declarations that do not fit in `add_usage`.
+    fn decls() -> Vec<Decl<C>> {
+        vec![]
+    }
     fn new<'a, 'b>(c: &C, args: &mut ParsedArgs<'a, 'b>) -> Result<Self, ArgsParseErr<'b>>;
     fn desc_act() -> &'static str;
     fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>);
@@ -201,6 +1239,13 @@ pub trait Args<C>: Sized {
     fn default(c: &C) -> Self;
 
     fn run(self, c: &C) -> Result<(), String>;
+    /// Like `run`, returning the result of the act to programmatic callers through `Acts::run`.
+    /// Leaves with a result serialize it here, and leaves with their own exit codes return them in `RunErr`.
+    fn output(self, c: &C) -> Result<serde_json::Value, RunErr> {
+        self.run(c)
+            .map(|()| serde_json::Value::Null)
+            .map_err(RunErr::from)
+    }
 }
 
 pub trait Parse<'a>
@@ -209,6 +1254,14 @@ where
 {
     fn parse(i: Arg<'a>) -> Result<Self, ParseErr<'a>>;
     fn desc() -> &'static str;
+    /// Candidates for a partially typed value.
+    fn complete(_i: &str) -> Vec<String> {
+        vec![]
+    }
+    /// Asks for a value in the terminal, starting from `d`.
+    fn prompt(p: &str, d: Option<&str>) -> Result<String, InquireError> {
+        Text::new(p).with_initial_value(d.unwrap_or("")).prompt()
+    }
 }
 #[derive(Debug)]
 pub struct ParseErr<'a> {
@@ -233,3 +1286,362 @@ impl<T: Display> Into<Vec<T>> for DisplayVec<T> {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A tree written the way the derives would write it.
+    struct C;
+
+    struct Main;
+    impl Acts<C> for Main {
+        fn opts() -> Vec<&'static str> {
+            vec!["shot", "headset"]
+        }
+        fn aliases() -> Vec<Alias> {
+            vec![Alias::new("snap", "shot")]
+        }
+        fn next_impl<'a>(
+            c: &C,
+            s: &mut ParseCtx<'a>,
+            a: &Arg<'a>,
+            args: &[Arg<'a>],
+        ) -> Result<(), ActsErr<'a>> {
+            match *a {
+                "shot" => {
+                    s.pfx.push(a);
+                    Shot::next(c, s, args)
+                }
+                "headset" => {
+                    s.pfx.push(a);
+                    Headset::next(c, s, args)
+                }
+                _ => Err(ActsErr::UnknownAct(s.to_owned(), a)),
+            }
+        }
+        fn desc_act() -> &'static str {
+            "test"
+        }
+        fn usage_v() -> Vec<[&'static str; 2]> {
+            vec![
+                ["shot", Shot::desc_act()],
+                ["headset", <Headset as Acts<C>>::desc_act()],
+            ]
+        }
+        fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>) {
+            let mut q = pfx.clone();
+            q.push("shot");
+            Shot::add_paths(&q, p);
+            let mut q = pfx.clone();
+            q.push("headset");
+            <Headset as Acts<C>>::add_paths(&q, p);
+        }
+    }
+
+    struct Headset;
+    impl Acts<C> for Headset {
+        fn opts() -> Vec<&'static str> {
+            vec!["con", "dis"]
+        }
+        fn aliases() -> Vec<Alias> {
+            vec![Alias {
+                deprecated: true,
+                ..Alias::new("off", "dis")
+            }]
+        }
+        fn hidden() -> Vec<&'static str> {
+            vec!["dis"]
+        }
+        fn add_opts(c: &C, r: &mut Vec<[String; 4]>) {
+            r.push(<String as Parse2<C>>::desc2(
+                Init::None,
+                "Adapter.",
+                "--adapter",
+                c,
+            ));
+        }
+        fn next_impl<'a>(
+            c: &C,
+            s: &mut ParseCtx<'a>,
+            a: &Arg<'a>,
+            args: &[Arg<'a>],
+        ) -> Result<(), ActsErr<'a>> {
+            match *a {
+                "con" => {
+                    s.pfx.push(a);
+                    Con::next(c, s, args)
+                }
+                "dis" => {
+                    s.pfx.push(a);
+                    Dis::next(c, s, args)
+                }
+                _ => Err(ActsErr::UnknownAct(s.to_owned(), a)),
+            }
+        }
+        fn desc_act() -> &'static str {
+            "Headset controls."
+        }
+        fn usage_v() -> Vec<[&'static str; 2]> {
+            vec![["con", Con::desc_act()], ["dis", Dis::desc_act()]]
+        }
+        fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>) {
+            let mut q = pfx.clone();
+            q.push("con");
+            Con::add_paths(&q, p);
+            let mut q = pfx.clone();
+            q.push("dis");
+            Dis::add_paths(&q, p);
+        }
+    }
+
+    struct Shot {
+        path: String,
+        tags: Vec<String>,
+        force: bool,
+    }
+    impl Args<C> for Shot {
+        fn new<'a, 'b>(c: &C, args: &mut ParsedArgs<'a, 'b>) -> Result<Self, ArgsParseErr<'b>> {
+            Ok(Self {
+                path: <String as Parse2<C>>::parse2(Init::Const("/a".into()), "--path", c, args)
+                    .map_err(|e| ArgsParseErr::Arg("--path", e, Self::usage(c)))?,
+                tags: <Vec<String> as Parse2<C>>::parse2(Init::None, "--tags", c, args)
+                    .map_err(|e| ArgsParseErr::Arg("--tags", e, Self::usage(c)))?,
+                force: <bool as Parse2<C>>::parse2(Init::None, "--force", c, args)
+                    .map_err(|e| ArgsParseErr::Arg("--force", e, Self::usage(c)))?,
+            })
+        }
+        fn desc_act() -> &'static str {
+            "Take a screenshot."
+        }
+        fn decls() -> Vec<Decl<C>> {
+            vec![
+                Decl {
+                    short: Some('p'),
+                    pos: true,
+                    env: Some("FPR_CLI_TEST_PATH"),
+                    ..Decl::new("--path")
+                },
+                Decl {
+                    pos: true,
+                    ..Decl::new("--tags")
+                },
+                Decl {
+                    short: Some('f'),
+                    ..Decl::new("--force")
+                },
+            ]
+        }
+        fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>) {
+            p.push(pfx.clone())
+        }
+        fn add_usage(c: &C, r: &mut Vec<[String; 4]>) {
+            r.push(<String as Parse2<C>>::desc2(
+                Init::Const("/a".into()),
+                "Path.",
+                "--path",
+                c,
+            ));
+            r.push(<Vec<String> as Parse2<C>>::desc2(
+                Init::None,
+                "Tags.",
+                "--tags",
+                c,
+            ));
+            r.push(<bool as Parse2<C>>::desc2(
+                Init::None,
+                "Force.",
+                "--force",
+                c,
+            ));
+        }
+        fn default(c: &C) -> Self {
+            Self {
+                path: <String as Parse2<C>>::default2(c, Init::Const("/a".into())),
+                tags: vec![],
+                force: false,
+            }
+        }
+        fn run(self, _: &C) -> Result<(), String> {
+            Ok(())
+        }
+        fn output(self, _: &C) -> Result<serde_json::Value, RunErr> {
+            if self.path == "/busy" {
+                return Err(RunErr::new("Busy.", 75));
+            }
+            Ok(serde_json::json!({"path": self.path, "tags": self.tags, "force": self.force}))
+        }
+    }
+
+    struct Con {
+        adapter: String,
+    }
+    impl Args<C> for Con {
+        fn new<'a, 'b>(c: &C, args: &mut ParsedArgs<'a, 'b>) -> Result<Self, ArgsParseErr<'b>> {
+            Ok(Self {
+                adapter: <String as Parse2<C>>::parse2(Init::None, "--adapter", c, args)
+                    .map_err(|e| ArgsParseErr::Arg("--adapter", e, Self::usage(c)))?,
+            })
+        }
+        fn desc_act() -> &'static str {
+            "Connect."
+        }
+        fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>) {
+            p.push(pfx.clone())
+        }
+        fn add_usage(c: &C, r: &mut Vec<[String; 4]>) {
+            r.push(<String as Parse2<C>>::desc2(
+                Init::None,
+                "Adapter.",
+                "--adapter",
+                c,
+            ));
+        }
+        fn default(_: &C) -> Self {
+            Self {
+                adapter: String::new(),
+            }
+        }
+        fn run(self, _: &C) -> Result<(), String> {
+            Ok(())
+        }
+        fn output(self, _: &C) -> Result<serde_json::Value, RunErr> {
+            Ok(self.adapter.into())
+        }
+    }
+
+    struct Dis;
+    impl Args<C> for Dis {
+        fn new<'a, 'b>(_: &C, _: &mut ParsedArgs<'a, 'b>) -> Result<Self, ArgsParseErr<'b>> {
+            Ok(Self)
+        }
+        fn desc_act() -> &'static str {
+            "Disconnect."
+        }
+        fn add_paths<'a>(pfx: &Vec<Arg<'a>>, p: &mut Vec<Vec<Arg<'a>>>) {
+            p.push(pfx.clone())
+        }
+        fn add_usage(_: &C, _: &mut Vec<[String; 4]>) {}
+        fn default(_: &C) -> Self {
+            Self
+        }
+        fn run(self, _: &C) -> Result<(), String> {
+            Ok(())
+        }
+        fn output(self, _: &C) -> Result<serde_json::Value, RunErr> {
+            Ok("dis".into())
+        }
+    }
+
+    fn call(a: &[&str]) -> Result<serde_json::Value, Exit> {
+        Main::call(&C, "test", a)
+    }
+    /// The code and kind of a failed call.
+    fn fail(a: &[&str]) -> (i32, ExitKind) {
+        let e = call(a).unwrap_err();
+        (e.code(), e.kind())
+    }
+
+    #[test]
+    fn call_output() {
+        assert_eq!(
+            call(&["shot", "/x", "a", "b", "-f"]).unwrap(),
+            serde_json::json!({"path": "/x", "tags": ["a", "b"], "force": true})
+        );
+        assert_eq!(
+            call(&["shot", "--tags", "a", "--tags", "b"]).unwrap()["tags"],
+            serde_json::json!(["a", "b"])
+        );
+        assert_eq!(
+            call(&["shot", "--path", "--", "--x"]).unwrap()["path"],
+            "--x"
+        );
+        assert_eq!(fail(&["shot", "/busy"]), (75, ExitKind::Run));
+    }
+    #[test]
+    fn call_errors() {
+        assert_eq!(fail(&[]), (2, ExitKind::ExpectedAct));
+        assert_eq!(fail(&["nope"]), (2, ExitKind::UnknownAct));
+        assert_eq!(fail(&["shot", "--nope"]), (2, ExitKind::Args));
+        assert_eq!(fail(&["shot", "-f", "-f"]), (2, ExitKind::Args));
+        assert_eq!(
+            fail(&["shot", "--path", "a", "--path", "b"]),
+            (2, ExitKind::Args)
+        );
+        // Missing input is an error rather than a prompt.
+        assert_eq!(fail(&["headset", "con"]), (2, ExitKind::Args));
+        assert_eq!(fail(&["shot", "--interactive"]), (2, ExitKind::Args));
+        let s = ParseCtx::default();
+        let e = Main::call_with(&C, s, "test", &["headset", "con", "--no-input"]).unwrap_err();
+        assert_eq!(e.code(), 2);
+    }
+    #[test]
+    fn help() {
+        for a in [
+            &["--help"][..],
+            &["help"],
+            &["help", "headset"],
+            &["headset", "-h"],
+            &["shot", "--help"],
+            &["help", "headset", "con"],
+        ] {
+            assert_eq!(fail(a), (0, ExitKind::Help), "{a:?}");
+        }
+    }
+    #[test]
+    fn aliases() {
+        assert_eq!(call(&["snap", "/x"]).unwrap()["path"], "/x");
+        assert_eq!(call(&["headset", "off"]).unwrap(), "dis");
+        assert_eq!(call(&["headset", "dis"]).unwrap(), "dis");
+        let l = Main::list(&C);
+        assert!(l.contains(&vec!["headset", "con"]));
+        assert!(!l.contains(&vec!["headset", "dis"]));
+        assert_eq!(Main::check(&C), Ok(()));
+    }
+    #[test]
+    fn node_opts() {
+        assert_eq!(
+            call(&["headset", "--adapter", "hci1", "con"]).unwrap(),
+            "hci1"
+        );
+        assert_eq!(
+            call(&["headset", "--adapter=hci1", "con", "--adapter", "hci2"]).unwrap(),
+            "hci2"
+        );
+        assert_eq!(
+            fail(&["headset", "--adapter", "hci1", "dis"]),
+            (2, ExitKind::Args)
+        );
+        assert_eq!(fail(&["headset", "--nope", "con"]), (2, ExitKind::Args));
+    }
+    #[test]
+    fn env_and_config() {
+        let cfg = serde_json::json!({"shot": {"path": "/cfg", "tags": ["c"]}});
+        let path = |a: &[&str]| {
+            let s = ParseCtx {
+                cfg: Some(cfg.clone()),
+                no_input: true,
+                ..Default::default()
+            };
+            Main::call_with(&C, s, "test", a).unwrap()["path"].to_owned()
+        };
+        assert_eq!(call(&["shot"]).unwrap()["path"], "/a");
+        assert_eq!(path(&["shot"]), "/cfg");
+        std::env::set_var("FPR_CLI_TEST_PATH", "/env");
+        assert_eq!(path(&["shot"]), "/env");
+        assert_eq!(path(&["shot", "/x"]), "/x");
+        std::env::remove_var("FPR_CLI_TEST_PATH");
+    }
+    #[test]
+    fn complete() {
+        let c = |a: &[&str]| Main::complete(&C, a);
+        assert_eq!(c(&["sh"]), ["shot"]);
+        assert_eq!(c(&["headset", ""]), ["con"]);
+        assert_eq!(c(&["headset", "--ad"]), ["--adapter"]);
+        assert_eq!(c(&["headset", "--adapter", "hci1", "c"]), ["con"]);
+        assert_eq!(c(&["shot", "--fo"]), ["--force"]);
+        let s = Main::completion(&C, Shell::Bash, "test");
+        assert!(s.contains("'headset') w='con --adapter'; v='--adapter'"));
+        assert!(!s.contains("'headset dis'"));
+    }
+}
@@ -4,16 +4,25 @@ pub enum Parse2Err {
     ExpectedOne,
     Rquired,
     ExpectedAtLeastOne,
+    ExpectedNone,
+    Negated,
+    Repeated,
 }
 pub enum ArgParseErr<'a> {
     ParseErr(ParseErr<'a>),
     Parse2Err(Parse2Err),
+    Inquire(InquireError),
 }
 impl<'a> From<ParseErr<'a>> for ArgParseErr<'a> {
     fn from(v: ParseErr<'a>) -> Self {
         ArgParseErr::ParseErr(v)
     }
 }
+impl<'a> From<InquireError> for ArgParseErr<'a> {
+    fn from(v: InquireError) -> Self {
+        ArgParseErr::Inquire(v)
+    }
+}
 impl<'a> From<Parse2Err> for ArgParseErr<'a> {
     fn from(v: Parse2Err) -> Self {
         ArgParseErr::Parse2Err(v)
@@ -21,22 +30,50 @@ impl<'a> From<Parse2Err> for ArgParseErr<'a> {
 }
 pub enum ArgsParseErr<'a> {
     UnexpectedToken(Arg<'a>, String),
-    Help(String),
-    UnknownArgs(Vec<Arg<'a>>, String),
+    /// `--help`, with the description of the act and `Args::help`.
+    Help(String, String),
+    /// The unknown options, pairs of an unknown option and the likely intended one, and usage.
+    UnknownArgs(Vec<Arg<'a>>, Vec<[String; 2]>, String),
     Arg(&'static str, ArgParseErr<'a>, String),
+    Duplicate(Arg<'a>, String),
+    /// `--interactive` when prompts are off, and usage.
+    NoPrompts(String),
+    /// Options given to the nodes on the way that the act does not declare, and usage.
+    NotRead(Vec<Arg<'a>>, String),
+    Ambiguous(Arg<'a>, Vec<String>, String),
 }
 impl<'a> Display for ArgsParseErr<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use ArgsParseErr::*;
         match self {
             UnexpectedToken(ref a, _) => write!(f, "Unexpected token '{a}'")?,
-            Help(_) => (),
-            UnknownArgs(ref a, _) => write!(
+            Help(ref d, _) => write!(f, "{d}")?,
+            UnknownArgs(ref a, ref m, _) => {
+                write!(
+                    f,
+                    "Unknown options '{}'",
+                    a.into_iter().map(|a| format!(r#""{a}""#)).join(", ")
+                )?;
+                for [a, m] in m {
+                    write!(f, "\nDid you mean '{m}' for '{a}'?")?;
+                }
+            }
+            Arg(ref a, ref e, _) => write!(f, "Error parsing option '{a}.'\n{e}")?,
+            Duplicate(ref a, _) => write!(f, "Option '{a}' is read by more than one field.")?,
+            NoPrompts(_) => write!(
                 f,
-                "Unknown options '{}'",
-                a.into_iter().map(|a| format!(r#""{a}""#)).join(", ")
+                "Option '{PFX}interactive' needs prompts, which are off without a terminal or with '{NO_INPUT}'."
+            )?,
+            NotRead(ref a, _) => write!(
+                f,
+                "Options {} were given before this act, which does not read them.",
+                a.iter().map(|a| format!("'{a}'")).join(", ")
+            )?,
+            Ambiguous(ref a, ref m, _) => write!(
+                f,
+                "Ambiguous option '{a}', could be {}.",
+                m.iter().map(|m| format!("'{m}'")).join(", ")
             )?,
-            Arg(ref a, ref e, _) => write!(f, "Error parsing option '{a}.'\n{e}")?,
         };
         Ok(())
     }
@@ -47,6 +84,7 @@ impl<'a> Display for ArgParseErr<'a> {
         match self {
             ParseErr(e) => write!(f, "{e}"),
             Parse2Err(e) => write!(f, "{e}"),
+            Inquire(e) => write!(f, "{e}"),
         }
     }
 }
@@ -66,12 +104,50 @@ impl Display for Parse2Err {
             ExpectedOne => write!(f, "Expected one value."),
             Rquired => write!(f, "Required."),
             ExpectedAtLeastOne => write!(f, "Expected one value minimum."),
+            ExpectedNone => write!(f, "Expected no value."),
+            Negated => write!(f, "Given together with its negation."),
+            Repeated => write!(f, "Given more than once."),
+        }
+    }
+}
+/// An error from `Args::output`, with the exit code of the process for it.
+#[derive(Clone, Debug)]
+pub struct RunErr {
+    pub msg: String,
+    pub code: i32,
+}
+impl RunErr {
+    pub fn new(msg: impl Into<String>, code: i32) -> Self {
+        Self {
+            msg: msg.into(),
+            code,
         }
     }
 }
+impl From<String> for RunErr {
+    fn from(v: String) -> Self {
+        Self::new(v, 1)
+    }
+}
+impl From<&str> for RunErr {
+    fn from(v: &str) -> Self {
+        Self::new(v, 1)
+    }
+}
+impl Display for RunErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
 pub enum ArgsErr<'a> {
-    Run(String),
+    Run(RunErr),
     Parse(ArgsParseErr<'a>),
+    Inquire(InquireError),
+}
+impl<'a> From<InquireError> for ArgsErr<'a> {
+    fn from(v: InquireError) -> Self {
+        Self::Inquire(v)
+    }
 }
 impl<'a> From<ArgsParseErr<'a>> for ArgsErr<'a> {
     fn from(v: ArgsParseErr<'a>) -> Self {
@@ -80,53 +156,159 @@ impl<'a> From<ArgsParseErr<'a>> for ArgsErr<'a> {
 }
 
 pub const PFX: &'static str = "--";
+/// Marks the next word as a value, never an option, even if it starts with `PFX`.
+/// Used within the parse for the values of `--k=v`; argv cannot contain it.
+pub const VAL: &str = "\0";
 #[derive(Debug)]
 pub struct Key {
     pub i: usize,
     pub used: bool,
 }
 #[derive(Debug)]
+pub struct Comp<'a> {
+    pub k: Arg<'a>,
+    pub i: Arg<'a>,
+    pub r: Vec<String>,
+}
+#[derive(Debug)]
 pub struct ParsedArgs<'b, 'c> {
     pub args: &'b [&'c str],
     pub keys: Vec<Key>,
+    /// When set, options are not parsed; the one named `k` completes `i` instead.
+    pub comp: Option<Comp<'c>>,
+    /// Options where the last occurrence wins instead of repeating being an error.
+    pub last: Vec<&'static str>,
+    /// Options that were consumed more than once, i.e. a definition with duplicate names.
+    pub dup: Vec<&'c str>,
+    /// Prompt for required options that are missing.
+    pub ask: bool,
+    /// When set, every option is prompted for.
+    pub form: Option<Form>,
+    /// Answers given at prompts, for the summary.
+    pub answers: Vec<[String; 2]>,
+    /// Tokens that reproduce the answers on the command line.
+    pub cmd: Vec<String>,
+    /// Where answers at prompts are kept. They are leaked without it.
+    pub own: Option<&'c Own>,
+}
+
+#[derive(Debug)]
+pub struct Form {
+    /// The usage table, for labels.
+    pub u: Vec<[String; 4]>,
 }
 
 impl<'b, 'c> ParsedArgs<'b, 'c> {
+    /// Returns true in completion mode, after recording candidates if `k` is the option being completed.
+    pub fn complete(&mut self, k: &str, f: fn(&str) -> Vec<String>) -> bool {
+        match self.comp {
+            Some(ref mut e) => {
+                if e.k == k {
+                    e.r = f(e.i);
+                }
+                true
+            }
+            None => false,
+        }
+    }
     pub fn consume(&mut self, name: &str) -> Option<&[&'c str]> {
-        let (i, k) = self
-            .keys
-            .iter_mut()
-            .enumerate()
-            .find(|(_, k)| self.args[k.i] == name)?;
-        assert!(
-            !k.used,
-            "A token should not ever be consumed twice. Probably a duplicate argument: {name}"
-        );
+        let i = self.keys.iter().position(|k| self.args[k.i] == name)?;
+        Some(self.take(i))
+    }
+    /// Like `consume`, but for every occurrence of `name`.
+    pub fn consume_all(&mut self, name: &str) -> Option<Vec<&'b [&'c str]>> {
+        let i = (0..self.keys.len())
+            .filter(|&i| self.args[self.keys[i].i] == name)
+            .collect_vec();
+        if i.is_empty() {
+            return None;
+        }
+        Some(i.into_iter().map(|i| self.take(i)).collect())
+    }
+    /// In form mode, consumes `k` and returns its label along with its values on the command line.
+    pub fn form(&mut self, k: &str) -> Option<(String, Option<Vec<&'c str>>)> {
+        let l = match self.form.as_ref()?.u.iter().find(|u| u[0] == k) {
+            Some(u) => format!("{} ({k})", u[2]),
+            None => k.to_owned(),
+        };
+        let a = self.consume_all(k).map(|a| a.concat());
+        Some((l, a))
+    }
+    /// Records the answer `v` for `k`, given on the command line as `a`.
+    pub fn answer(&mut self, k: &str, v: String, a: Vec<String>) {
+        self.answers.push([k.to_owned(), v]);
+        self.cmd.extend(a);
+    }
+    /// Like `consume`, but an option that takes one value must not be repeated unless it is in `last`.
+    pub fn consume_one(&mut self, name: &str) -> Result<Option<&'b [&'c str]>, Parse2Err> {
+        let Some(mut a) = self.consume_all(name) else {
+            return Ok(None);
+        };
+        if a.len() > 1 && !self.last.contains(&name) {
+            Err(Parse2Err::Repeated)?
+        }
+        Ok(a.pop())
+    }
+    fn take(&mut self, i: usize) -> &'b [&'c str] {
+        let k = &mut self.keys[i];
+        if k.used {
+            self.dup.push(self.args[k.i]);
+        }
         k.used = true;
         let idx = k.i + 1;
-        Some(if i == self.keys.len() - 1 {
+        if i == self.keys.len() - 1 {
             &self.args[idx..]
         } else if self.args[self.keys[i + 1].i] == PFX {
             &self.args[self.keys[i + 1].i + 1..]
         } else {
             &self.args[idx..self.keys[i + 1].i]
-        })
+        }
+    }
+}
+
+/// Strings made while parsing, such as values from the environment or answers at prompts.
+/// They are borrowed like argv until the call that owns this ends.
+#[derive(Default)]
+pub struct Own(typed_arena::Arena<String>);
+impl Own {
+    pub fn add(&self, s: String) -> &str {
+        self.0.alloc(s)
+    }
+}
+impl std::fmt::Debug for Own {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Own({})", self.0.len())
+    }
+}
+/// Keeps `s` in `o`, or leaks it when there is none.
+fn keep(o: Option<&Own>, s: String) -> &str {
+    match o {
+        Some(o) => o.add(s),
+        None => s.leak(),
     }
 }
 
 pub enum ParsedArgsErr<'a> {
     UnexpectedToken(Arg<'a>),
+    Ambiguous(Arg<'a>, Vec<String>),
 }
 impl<'b, 'c> ParsedArgs<'b, 'c> {
     pub fn new(args: &'b [&'c str]) -> Result<ParsedArgs<'b, 'c>, ParsedArgsErr<'c>> {
+        Self::with_values(args, &[])
+    }
+    /// Like `new`, where the words at `v` are values even if they start with `PFX`, as from `unmark`.
+    pub fn with_values(
+        args: &'b [&'c str],
+        v: &[usize],
+    ) -> Result<ParsedArgs<'b, 'c>, ParsedArgsErr<'c>> {
         let mut end = false;
         let r = ParsedArgs {
             args,
             keys: args
                 .iter()
                 .enumerate()
-                .filter(|&(_, a)| {
-                    if end {
+                .filter(|&(i, a)| {
+                    if end || v.contains(&i) {
                         false
                     } else {
                         let pfx = a.starts_with(PFX);
@@ -140,6 +322,14 @@ impl<'b, 'c> ParsedArgs<'b, 'c> {
                 })
                 .map(|(i, _)| Key { i, used: false })
                 .collect(),
+            comp: None,
+            last: vec![],
+            dup: vec![],
+            ask: false,
+            form: None,
+            answers: vec![],
+            cmd: vec![],
+            own: None,
         };
 
         if !r.keys.is_empty() && r.keys[0].i != 0 {
@@ -150,6 +340,472 @@ impl<'b, 'c> ParsedArgs<'b, 'c> {
     }
 }
 
+pub struct Decl<C> {
+    pub k: &'static str,
+    /// Value candidates that need the context, e.g. device names.
+    pub comp: Option<fn(&C) -> Vec<String>>,
+    pub short: Option<char>,
+    /// Also taken from values that no option claims, in declaration order, skipping those given as options. A list takes the rest.
+    pub pos: bool,
+    /// Later occurrences override earlier ones instead of being an error.
+    pub last: bool,
+    /// Environment variable consulted when the option is not on the command line.
+    pub env: Option<&'static str>,
+    /// Longer help, shown under the usage table in `--help`.
+    pub about: About,
+}
+impl<C> Decl<C> {
+    pub fn new(k: &'static str) -> Self {
+        Self {
+            k,
+            comp: None,
+            short: None,
+            pos: false,
+            last: false,
+            env: None,
+            about: About::default(),
+        }
+    }
+}
+
+/// A short alias such as `-p` for `--path`. `v` is set when the option takes a value.
+#[derive(Clone, Debug)]
+pub struct Short {
+    pub c: char,
+    pub k: &'static str,
+    pub v: bool,
+}
+
+/// Rewrites short options and `--k=v` into separate long form tokens so that `ParsedArgs` only sees `PFX` keys.
+/// `u` is the usage table, used to split `--k=a,b` for lists. `-h` is `--help` unless `s` takes it.
+/// Values given with the option, as in `--k=v` or `-pv`, are marked with `VAL` and end it.
+pub fn expand<'a>(args: &[Arg<'a>], s: &[Short], u: &[[String; 4]]) -> Vec<Arg<'a>> {
+    let mut r = vec![];
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        if a == PFX {
+            r.push(a);
+            r.extend(it);
+            break;
+        }
+        if let Some((k, v)) = a.strip_prefix(PFX).and_then(|_| a.split_once('=')) {
+            r.push(k);
+            match u.iter().any(|u| u[0] == k && is_list(&u[1])) {
+                true => r.extend(
+                    v.split(',')
+                        .filter(|v| !v.is_empty())
+                        .flat_map(|v| [VAL, v]),
+                ),
+                false => r.extend([VAL, v]),
+            }
+            continue;
+        }
+        match short(a, s) {
+            Some(e) => r.extend(e),
+            None if a == "-h" => r.push("--help"),
+            None => r.push(a),
+        }
+    }
+    r
+}
+/// The one of `c` that `i` is a prefix of, if any. Exact matches win; `Err` has the candidates when there are several.
+pub fn unique_prefix<'s>(i: &str, c: &[&'s str]) -> Result<Option<&'s str>, Vec<&'s str>> {
+    if let Some(&e) = c.iter().find(|&&c| c == i) {
+        return Ok(Some(e));
+    }
+    if i.is_empty() {
+        return Ok(None);
+    }
+    let m = c.iter().copied().filter(|c| c.starts_with(i)).collect_vec();
+    match m.len() {
+        0 => Ok(None),
+        1 => Ok(Some(m[0])),
+        _ => Err(m),
+    }
+}
+
+/// Replaces unique prefixes of the keys `k`, including in `--k=v`, with the keys. Runs before `expand`.
+pub fn unabbrev<'a>(
+    args: &[Arg<'a>],
+    k: &[String],
+    o: &'a Own,
+) -> Result<Vec<Arg<'a>>, ParsedArgsErr<'a>> {
+    let mut r = vec![];
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        if a == PFX {
+            r.push(a);
+            r.extend(it);
+            break;
+        }
+        if !a.starts_with(PFX) {
+            r.push(a);
+            continue;
+        }
+        let (n, v) = match a.split_once('=') {
+            Some((n, v)) => (n, Some(v)),
+            None => (a, None),
+        };
+        match unique_prefix(n, &k.iter().map(|k| k.as_str()).collect_vec()) {
+            Ok(Some(e)) if e != n => r.push(match v {
+                Some(v) => o.add(format!("{e}={v}")),
+                None => o.add(e.to_owned()),
+            }),
+            Ok(_) => r.push(a),
+            Err(m) => {
+                return Err(ParsedArgsErr::Ambiguous(
+                    a,
+                    m.into_iter().map(|m| m.to_owned()).collect(),
+                ))
+            }
+        }
+    }
+    Ok(r)
+}
+
+/// Whether `args` sets `k` or its negation as an option, not as a value.
+fn given(args: &[Arg], k: &str) -> bool {
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        match a {
+            VAL => {
+                it.next();
+            }
+            PFX => break,
+            a if a == k || a == neg(k) => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Renders `args`, with values marked by `VAL`, as words that parse to the same options.
+/// A value that starts with `-` is given with its option as `--k=v`.
+pub fn to_words(args: &[Arg]) -> Vec<String> {
+    let mut r = vec![];
+    // The option the values are for, and whether a plain value still goes to it.
+    let (mut k, mut o) = ("", false);
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        if a != VAL {
+            if a.starts_with(PFX) {
+                (k, o) = (a, true);
+            }
+            r.push(a.to_owned());
+            continue;
+        }
+        let Some(&v) = it.next() else {
+            break;
+        };
+        if v.starts_with('-') {
+            if o && r.last().is_some_and(|l| l == k) {
+                r.pop();
+            }
+            r.push(format!("{k}={v}"));
+            o = false;
+        } else {
+            if !o {
+                r.push(k.to_owned());
+                o = true;
+            }
+            r.push(v.to_owned());
+        }
+    }
+    r
+}
+
+/// Drops the `VAL` marks from `args`, returning the rest with the indices of the marked values.
+pub fn unmark<'a>(args: &[Arg<'a>]) -> (Vec<Arg<'a>>, Vec<usize>) {
+    let mut r = vec![];
+    let mut v = vec![];
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        if a != VAL {
+            r.push(a);
+        } else if let Some(&a) = it.next() {
+            v.push(r.len());
+            r.push(a);
+        }
+    }
+    (r, v)
+}
+
+/// The values of a config file entry as they would appear on the command line.
+pub fn values(v: &serde_json::Value) -> Vec<String> {
+    use serde_json::Value::*;
+    match v {
+        String(s) => vec![s.to_owned()],
+        Array(a) => a.iter().flat_map(values).collect(),
+        Null => vec![],
+        v => vec![v.to_string()],
+    }
+}
+
+/// Options given to a node as key and value, from `leading`.
+pub type NodeOpts<'a> = Vec<(Arg<'a>, Option<Arg<'a>>)>;
+
+/// Reads the options of the usage table `u` at the start of `args`, up to the first word that is not an option.
+/// Returns them as key and value with the number of words they took, or the first option that `u` does not declare.
+pub fn leading<'a>(args: &[Arg<'a>], u: &[[String; 4]]) -> Result<(NodeOpts<'a>, usize), Arg<'a>> {
+    let mut r = vec![];
+    let mut i = 0;
+    while let Some(&a) = args.get(i) {
+        if a == PFX || !a.starts_with('-') {
+            break;
+        }
+        let (k, v) = match a.split_once('=') {
+            Some((k, v)) => (k, Some(v)),
+            None => (a, None),
+        };
+        let Some(d) = declared(k, u) else {
+            return Err(a);
+        };
+        i += 1;
+        let v = match v {
+            Some(v) => Some(v),
+            None if takes_value(&d[1]) => {
+                i += 1;
+                args.get(i - 1).copied()
+            }
+            None => None,
+        };
+        r.push((k, v));
+    }
+    Ok((r, i))
+}
+
+/// Prepends the options `o`, given to the nodes on the way as from `leading`, that `args` does not set.
+/// Fails with the ones that the usage table `u` does not declare, since nothing would read them.
+pub fn inherit<'a>(
+    args: Vec<Arg<'a>>,
+    o: &[(Arg<'a>, Option<Arg<'a>>)],
+    u: &[[String; 4]],
+) -> Result<Vec<Arg<'a>>, Vec<Arg<'a>>> {
+    let mut r = vec![];
+    let mut n = vec![];
+    for &(k, v) in o {
+        match declared(k, u) {
+            Some(d) if !given(&args, &d[0]) => {
+                r.push(k);
+                r.extend(v.into_iter().flat_map(|v| [VAL, v]));
+            }
+            Some(_) => (),
+            None => n.push(k),
+        }
+    }
+    if !n.is_empty() {
+        return Err(n);
+    }
+    r.extend(args);
+    Ok(r)
+}
+
+/// The row of `u` for the option `k`, which may be the negation of a flag.
+fn declared<'u>(k: &str, u: &'u [[String; 4]]) -> Option<&'u [String; 4]> {
+    u.iter()
+        .find(|u| u[0] == k || (u[1] == FLAG && neg(&u[0]) == k))
+}
+
+/// Prepends the options set in config section `o` but not in `args`. Values are kept in `w` like in `env`.
+pub fn config<'a>(
+    args: Vec<Arg<'a>>,
+    o: Option<&serde_json::Map<String, serde_json::Value>>,
+    u: &[[String; 4]],
+    w: &'a Own,
+) -> Vec<Arg<'a>> {
+    let Some(o) = o else {
+        return args;
+    };
+    let mut r: Vec<Arg<'a>> = vec![];
+    for u in u {
+        let k = &u[0];
+        let Some(v) = o.get(k.trim_start_matches(PFX)) else {
+            continue;
+        };
+        if given(&args, k) {
+            continue;
+        }
+        match (u[1] == FLAG, v) {
+            (true, serde_json::Value::Bool(true)) => r.push(w.add(k.to_owned())),
+            (true, serde_json::Value::Bool(false)) => r.push(w.add(neg(k))),
+            _ => {
+                r.push(w.add(k.to_owned()));
+                r.extend(values(v).into_iter().flat_map(|v| [VAL, w.add(v)]));
+            }
+        }
+    }
+    r.extend(args);
+    r
+}
+
+/// Prepends the options in `e`, given as `(key, variable)`, that are set in the environment but not in `args`.
+/// The values are kept in `o` so that they can be borrowed like argv for the rest of the parse.
+pub fn env<'a>(
+    args: Vec<Arg<'a>>,
+    e: &[(&'static str, &'static str)],
+    u: &[[String; 4]],
+    o: &'a Own,
+) -> Vec<Arg<'a>> {
+    let mut r = vec![];
+    for &(k, v) in e {
+        if given(&args, k) {
+            continue;
+        }
+        let Ok(v) = env_var(v) else {
+            continue;
+        };
+        match (u.iter().any(|u| u[0] == k && u[1] == FLAG), v.as_str()) {
+            (true, "1" | "true") => r.push(k),
+            (true, "0" | "false" | "") => r.push(o.add(neg(k))),
+            _ => r.extend(expand(&[o.add(format!("{k}={v}"))], &[], u)),
+        }
+    }
+    r.extend(args);
+    r
+}
+
+/// How many values key `k` takes. `None` means no limit.
+fn arity(k: &str, u: &[[String; 4]]) -> Option<usize> {
+    if k == format!("{PFX}help") || k == format!("{PFX}interactive") {
+        return Some(0);
+    }
+    match u
+        .iter()
+        .find(|u| u[0] == k || (u[1] == FLAG && neg(&u[0]) == k))
+    {
+        Some(u) if u[1] == FLAG => Some(0),
+        Some(u) if is_list(&u[1]) => None,
+        Some(_) => Some(1),
+        None => None,
+    }
+}
+
+/// Takes the options of the usage table `u`, or their shorts `s`, and their values out of `args`, wherever they are before `--`.
+/// Returns them in long form with their values marked by `VAL`, and the rest. A list takes one value per occurrence.
+/// Every token that names one is taken, even if it was meant as another option's value.
+/// Shorts are matched whole, so bundles like `-vq` are left in the rest.
+pub fn take_opts<'a>(
+    args: &[Arg<'a>],
+    u: &[[String; 4]],
+    s: &[Short],
+) -> (Vec<Arg<'a>>, Vec<Arg<'a>>) {
+    let mut g = vec![];
+    let mut r = vec![];
+    let mut it = args.iter();
+    while let Some(&a) = it.next() {
+        if a == PFX {
+            r.push(a);
+            r.extend(it);
+            break;
+        }
+        let (k, v) = match a.split_once('=') {
+            Some((k, v)) => (k, Some(v)),
+            None => (a, None),
+        };
+        let k = match s.iter().find(|s| k == format!("-{}", s.c)) {
+            Some(s) => s.k,
+            None => k,
+        };
+        let Some(o) = u
+            .iter()
+            .find(|u| u[0] == k || (u[1] == FLAG && neg(&u[0]) == k))
+        else {
+            r.push(a);
+            continue;
+        };
+        g.push(k);
+        let v = match v {
+            Some(v) => Some(v),
+            None if takes_value(&o[1]) => it.next().copied(),
+            None => None,
+        };
+        g.extend(v.into_iter().flat_map(|v| [VAL, v]));
+    }
+    (g, r)
+}
+
+/// Moves values that no option claims to the front as `--k v`, where `p` names the positional options in order.
+/// Values are marked with `VAL`, as are the words after `--`, which go to an option still waiting for values if there is one.
+pub fn positional<'a>(
+    args: Vec<Arg<'a>>,
+    p: &[&'static str],
+    u: &[[String; 4]],
+) -> Result<Vec<Arg<'a>>, ParsedArgsErr<'a>> {
+    let mut o = vec![];
+    let mut v = vec![];
+    let mut n = Some(0);
+    let mut it = args.into_iter();
+    while let Some(a) = it.next() {
+        // A marked value belongs to the option before it, which it ends.
+        if a == VAL {
+            o.push(a);
+            o.extend(it.next());
+            n = Some(0);
+            continue;
+        }
+        if a == PFX {
+            match n {
+                Some(0) => v.extend(it),
+                _ => o.extend(it.flat_map(|a| [VAL, a])),
+            }
+            break;
+        }
+        if a.starts_with(PFX) {
+            n = arity(a, u);
+            o.push(a);
+        } else if n != Some(0) {
+            n = n.map(|n| n - 1);
+            o.push(a);
+        } else {
+            v.push(a);
+        }
+    }
+
+    let mut r = vec![];
+    let mut v = v.into_iter().peekable();
+    // Positionals given as options are skipped, so the values go to the next ones.
+    for &k in p.iter().filter(|&&k| !o.contains(&k)) {
+        if v.peek().is_none() {
+            break;
+        }
+        r.push(k);
+        match u.iter().any(|u| u[0] == k && is_list(&u[1])) {
+            true => r.extend(v.by_ref().flat_map(|a| [VAL, a])),
+            false => r.extend(v.next().into_iter().flat_map(|a| [VAL, a])),
+        }
+    }
+    if let Some(a) = v.next() {
+        return Err(ParsedArgsErr::UnexpectedToken(a));
+    }
+    r.extend(o);
+    Ok(r)
+}
+
+fn short<'a>(a: Arg<'a>, s: &[Short]) -> Option<Vec<Arg<'a>>> {
+    let b = a.strip_prefix('-')?;
+    if b.starts_with('-') {
+        return None;
+    }
+    let mut r = vec![];
+    for (i, c) in b.char_indices() {
+        let e = s.iter().find(|e| e.c == c)?;
+        r.push(e.k);
+        if e.v {
+            let v = &b[i + c.len_utf8()..];
+            let v = v.strip_prefix('=').unwrap_or(v);
+            if !v.is_empty() {
+                r.extend([VAL, v]);
+            }
+            break;
+        }
+    }
+    if r.is_empty() {
+        None
+    } else {
+        Some(r)
+    }
+}
+
 pub enum Init<C, T: Display> {
     None,
     Const(T),
@@ -172,6 +828,46 @@ impl<C, T: Display> Init<C, T> {
     }
 }
 
+/// Prompts with label `l` until the answer parses, starting from `d`. Answers are kept in `o`.
+fn ask<'a, T: Parse<'a>>(
+    o: Option<&'a Own>,
+    l: &str,
+    d: Option<&str>,
+) -> Result<T, ArgParseErr<'a>> {
+    loop {
+        match T::parse(keep(o, T::prompt(l, d)?)) {
+            Ok(v) => return Ok(v),
+            Err(e) => println!("{e}"),
+        }
+    }
+}
+/// Like `ask`, for a comma separated list.
+fn ask_list<'a, T: Parse<'a>>(
+    o: Option<&'a Own>,
+    l: &str,
+    d: &str,
+) -> Result<Vec<T>, ArgParseErr<'a>> {
+    loop {
+        let a = keep(
+            o,
+            Text::new(l)
+                .with_initial_value(d)
+                .with_help_message("Comma separated.")
+                .prompt()?,
+        );
+        match a
+            .split(',')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+            .map(T::parse)
+            .collect()
+        {
+            Ok(v) => return Ok(v),
+            Err(e) => println!("{e}"),
+        }
+    }
+}
+
 pub trait Parse2<'a, 'b, C>
 where
     Self: Sized,
@@ -196,7 +892,19 @@ impl<'a, 'b, C, T: Parse<'a> + Default> Parse2<'b, 'a, C> for T {
         c: &C,
         p: &mut ParsedArgs<'b, 'a>,
     ) -> Result<Self, ArgParseErr<'a>> {
-        match p.consume(k) {
+        if p.complete(k, T::complete) {
+            return Ok(Self::default2(c, i));
+        }
+        if let Some((l, a)) = p.form(k) {
+            let d = match a {
+                Some(a) => a.first().map(|a| a.to_string()),
+                None => i.get(c).map(|v| v.to_string()),
+            };
+            let v: T = ask(p.own, &l, d.as_deref())?;
+            p.answer(k, v.to_string(), vec![k.to_owned(), v.to_string()]);
+            return Ok(v);
+        }
+        match p.consume_one(k)? {
             Some(args) => {
                 if args.len() != 1 {
                     Err(Parse2Err::ExpectedOne)?
@@ -204,7 +912,15 @@ impl<'a, 'b, C, T: Parse<'a> + Default> Parse2<'b, 'a, C> for T {
                     Ok(T::parse(&args[0])?)
                 }
             }
-            None => Ok(i.get(c).ok_or(Parse2Err::Rquired)?),
+            None => match i.get(c) {
+                Some(v) => Ok(v),
+                None if p.ask => {
+                    let v: T = ask(p.own, &format!("{k} ({})", T::desc()), None)?;
+                    p.answer(k, v.to_string(), vec![k.to_owned(), v.to_string()]);
+                    Ok(v)
+                }
+                None => Err(Parse2Err::Rquired)?,
+            },
         }
     }
 
@@ -229,7 +945,26 @@ impl<'a, 'b, Ctx, T: Parse<'a>> Parse2<'b, 'a, Ctx> for Option<T> {
         c: &Ctx,
         p: &mut ParsedArgs<'b, 'a>,
     ) -> Result<Self, ArgParseErr<'a>> {
-        match p.consume(k) {
+        if p.complete(k, T::complete) {
+            return Ok(Self::default2(c, i));
+        }
+        if let Some((l, a)) = p.form(k) {
+            let d = match a {
+                Some(a) => a.first().map(|a| a.to_string()),
+                None => i.get(c).map(|v| v.to_string()),
+            };
+            if !Confirm::new(&format!("Set {k}?"))
+                .with_default(d.is_some())
+                .prompt()?
+            {
+                p.answer(k, "".to_owned(), vec![]);
+                return Ok(None);
+            }
+            let v: T = ask(p.own, &l, d.as_deref())?;
+            p.answer(k, v.to_string(), vec![k.to_owned(), v.to_string()]);
+            return Ok(Some(v));
+        }
+        match p.consume_one(k)? {
             Some(args) => {
                 if args.len() != 1 {
                     Err(Parse2Err::ExpectedOne)?
@@ -267,15 +1002,29 @@ impl<'a, 'b, Ctx, T: Parse<'a> + Display> Parse2<'b, 'a, Ctx> for Vec<T> {
         c: &Ctx,
         p: &mut ParsedArgs<'b, 'a>,
     ) -> Result<Self, ArgParseErr<'a>> {
-        match p.consume(k) {
+        if p.complete(k, T::complete) {
+            return Ok(Self::default2(c, i));
+        }
+        if let Some((l, a)) = p.form(k) {
+            let d = match a {
+                Some(a) => a.join(", "),
+                None => i.get(c).map(|v| v.to_string()).unwrap_or_default(),
+            };
+            let v: Vec<T> = ask_list(p.own, &l, &d)?;
+            let a = v.iter().flat_map(|v| [k.to_owned(), v.to_string()]);
+            p.answer(k, v.iter().join(", "), a.collect());
+            return Ok(v);
+        }
+        match p.consume_all(k) {
             Some(args) => {
+                if args.iter().any(|a| a.is_empty()) {
+                    Err(Parse2Err::ExpectedAtLeastOne)?
+                }
                 let args = args
                     .iter()
+                    .flat_map(|a| a.iter())
                     .map(|a| T::parse(a))
                     .collect::<Result<Vec<_>, _>>()?;
-                if args.is_empty() {
-                    Err(Parse2Err::ExpectedAtLeastOne)?
-                }
                 Ok(args)
             }
             None => Ok(match i.get(c) {
@@ -301,6 +1050,67 @@ impl<'a, 'b, Ctx, T: Parse<'a> + Display> Parse2<'b, 'a, Ctx> for Vec<T> {
     }
 }
 
+pub const FLAG: &str = "Flag";
+pub fn is_list(ty: &str) -> bool {
+    ty.starts_with("Vec<")
+}
+pub fn neg(k: &str) -> String {
+    format!("{PFX}no-{}", k.trim_start_matches(PFX))
+}
+
+impl<'a, 'b, Ctx> Parse2<'b, 'a, Ctx> for bool {
+    type I = bool;
+    fn parse2(
+        i: Init<Ctx, Self::I>,
+        k: &'static str,
+        c: &Ctx,
+        p: &mut ParsedArgs<'b, 'a>,
+    ) -> Result<Self, ArgParseErr<'a>> {
+        if p.complete(k, |_| vec![]) {
+            return Ok(Self::default2(c, i));
+        }
+        if let Some((l, a)) = p.form(k) {
+            let d = match (a, p.consume_all(&neg(k))) {
+                (Some(_), _) => true,
+                (None, Some(_)) => false,
+                (None, None) => i.get(c).unwrap_or(false),
+            };
+            let v = Confirm::new(&l).with_default(d).prompt()?;
+            let a = if v { k.to_owned() } else { neg(k) };
+            p.answer(k, v.to_string(), vec![a]);
+            return Ok(v);
+        }
+        let m = neg(k);
+        // Whether the flag, rather than its negation, is given last.
+        let l = p
+            .keys
+            .iter()
+            .rev()
+            .map(|e| p.args[e.i])
+            .find(|&a| a == k || a == m)
+            .map(|a| a == k);
+        let y = p.consume_all(k).unwrap_or_default();
+        let n = p.consume_all(&m).unwrap_or_default();
+        if y.iter().chain(&n).any(|a| !a.is_empty()) {
+            Err(Parse2Err::ExpectedNone)?
+        }
+        match (y.len(), n.len()) {
+            (0, 0) => Ok(Self::default2(c, i)),
+            (1, 0) | (0, 1) => Ok(y.len() == 1),
+            _ if p.last.contains(&k) => Ok(l == Some(true)),
+            (0, _) | (_, 0) => Err(Parse2Err::Repeated)?,
+            _ => Err(Parse2Err::Negated)?,
+        }
+    }
+
+    fn desc2(i: Init<Ctx, Self::I>, d: &'static str, k: &'static str, c: &Ctx) -> [String; 4] {
+        [k.into(), FLAG.into(), d.into(), i.to_string(c)]
+    }
+    fn default2(c: &Ctx, i: Init<Ctx, Self::I>) -> Self {
+        i.get(c).unwrap_or(false)
+    }
+}
+
 pub struct OptVec<T: Display>(pub Vec<T>);
 impl<T: Display> From<Vec<T>> for OptVec<T> {
     fn from(v: Vec<T>) -> Self {
@@ -320,10 +1130,24 @@ impl<'a, 'b, Ctx, T: Parse<'a>> Parse2<'b, 'a, Ctx> for OptVec<T> {
         c: &Ctx,
         p: &mut ParsedArgs<'b, 'a>,
     ) -> Result<Self, ArgParseErr<'a>> {
-        match p.consume(k) {
+        if p.complete(k, T::complete) {
+            return Ok(Self::default2(c, i));
+        }
+        if let Some((l, a)) = p.form(k) {
+            let d = match a {
+                Some(a) => a.join(", "),
+                None => i.get(c).map(|v| v.to_string()).unwrap_or_default(),
+            };
+            let v: Vec<T> = ask_list(p.own, &l, &d)?;
+            let a = v.iter().flat_map(|v| [k.to_owned(), v.to_string()]);
+            p.answer(k, v.iter().join(", "), a.collect());
+            return Ok(v.into());
+        }
+        match p.consume_all(k) {
             Some(args) => {
                 let args = args
                     .iter()
+                    .flat_map(|a| a.iter())
                     .map(|a| T::parse(a))
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(args.into())
@@ -360,3 +1184,289 @@ impl Display for FileExist {
         write!(f, "{}", self.s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u() -> Vec<[String; 4]> {
+        [
+            ("--verbose", FLAG),
+            ("--force", FLAG),
+            ("--path", "Req<String>"),
+            ("--tags", "Vec<String>"),
+        ]
+        .map(|(k, t)| [k.to_owned(), t.to_owned(), String::new(), String::new()])
+        .to_vec()
+    }
+    fn s() -> Vec<Short> {
+        vec![
+            Short {
+                c: 'v',
+                k: "--verbose",
+                v: false,
+            },
+            Short {
+                c: 'f',
+                k: "--force",
+                v: false,
+            },
+            Short {
+                c: 'p',
+                k: "--path",
+                v: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn expand_shorts() {
+        let e = |a: &[&'static str]| expand(a, &s(), &u());
+        assert_eq!(e(&["-vfp=x"]), ["--verbose", "--force", "--path", VAL, "x"]);
+        assert_eq!(e(&["-vpx"]), ["--verbose", "--path", VAL, "x"]);
+        assert_eq!(e(&["-p", "x"]), ["--path", "x"]);
+        assert_eq!(e(&["-vz"]), ["-vz"]);
+        assert_eq!(e(&["--", "-v"]), ["--", "-v"]);
+    }
+    #[test]
+    fn expand_help() {
+        assert_eq!(expand(&["-h"], &s(), &u()), ["--help"]);
+        let h = [Short {
+            c: 'h',
+            k: "--host",
+            v: true,
+        }];
+        assert_eq!(expand(&["-h", "a"], &h, &u()), ["--host", "a"]);
+    }
+    #[test]
+    fn expand_eq() {
+        let e = |a: &[&'static str]| expand(a, &[], &u());
+        assert_eq!(e(&["--tags=a,b"]), ["--tags", VAL, "a", VAL, "b"]);
+        assert_eq!(e(&["--tags=a,,b,"]), ["--tags", VAL, "a", VAL, "b"]);
+        assert_eq!(e(&["--path=a,b"]), ["--path", VAL, "a,b"]);
+        assert_eq!(e(&["--path=a=b"]), ["--path", VAL, "a=b"]);
+        assert_eq!(e(&["--path="]), ["--path", VAL, ""]);
+        assert_eq!(e(&["--path=--x"]), ["--path", VAL, "--x"]);
+        assert_eq!(e(&["--", "--path=a"]), ["--", "--path=a"]);
+    }
+
+    fn pos(a: &[&'static str], p: &[&'static str]) -> Result<Vec<&'static str>, &'static str> {
+        positional(a.to_vec(), p, &u()).map_err(|e| match e {
+            ParsedArgsErr::UnexpectedToken(a) | ParsedArgsErr::Ambiguous(a, _) => a,
+        })
+    }
+    #[test]
+    fn positional_values() {
+        let p = ["--path", "--tags"];
+        assert_eq!(
+            pos(&["/x", "a", "b"], &p),
+            Ok(vec!["--path", VAL, "/x", "--tags", VAL, "a", VAL, "b"])
+        );
+        assert_eq!(
+            pos(&["--verbose", "/x"], &p),
+            Ok(vec!["--path", VAL, "/x", "--verbose"])
+        );
+        assert_eq!(
+            pos(&["--path", "/x", "a"], &p),
+            Ok(vec!["--tags", VAL, "a", "--path", "/x"])
+        );
+        assert_eq!(pos(&["/x", "/y"], &["--path"]), Err("/y"));
+        assert_eq!(pos(&["/x"], &[]), Err("/x"));
+    }
+    #[test]
+    fn positional_end() {
+        let p = ["--path", "--tags"];
+        assert_eq!(
+            pos(&["--", "-x", "--y"], &p),
+            Ok(vec!["--path", VAL, "-x", "--tags", VAL, "--y"])
+        );
+        assert_eq!(
+            pos(&["--verbose", "--", "/x"], &p),
+            Ok(vec!["--path", VAL, "/x", "--verbose"])
+        );
+        assert_eq!(
+            pos(&["--tags", "--", "ls", "-la"], &p),
+            Ok(vec!["--tags", VAL, "ls", VAL, "-la"])
+        );
+    }
+    #[test]
+    fn positional_after_list() {
+        let p = ["--path", "--tags"];
+        // A list takes every value up to the next option, and the rest after `--`, so a positional goes before it.
+        assert_eq!(
+            pos(&["--tags", "a", "/x"], &p),
+            Ok(vec!["--tags", "a", "/x"])
+        );
+        assert_eq!(
+            pos(&["--tags", "a", "--", "/x"], &p),
+            Ok(vec!["--tags", "a", VAL, "/x"])
+        );
+    }
+
+    /// Runs `a` through the steps of a leaf's parse and returns each option with its values.
+    fn parse(a: &[&'static str]) -> Result<Vec<(&'static str, Vec<&'static str>)>, &'static str> {
+        let a = pos(&expand(a, &s(), &u()), &["--path", "--tags"])?;
+        let (a, v) = unmark(&a);
+        let Ok(mut p) = ParsedArgs::with_values(&a, &v) else {
+            return Err("parse");
+        };
+        let k = p.keys.iter().map(|k| a[k.i]).collect_vec();
+        Ok(k.into_iter()
+            .map(|k| (k, p.consume(k).unwrap_or_default().to_vec()))
+            .collect())
+    }
+    #[test]
+    fn parse_values() {
+        assert_eq!(parse(&["--", "--x"]), Ok(vec![("--path", vec!["--x"])]));
+        assert_eq!(
+            parse(&["/x", "--tags", "--", "--a"]),
+            Ok(vec![("--path", vec!["/x"]), ("--tags", vec!["--a"])])
+        );
+        assert_eq!(
+            parse(&["--tags", "--", "ls", "-la"]),
+            Ok(vec![("--tags", vec!["ls", "-la"])])
+        );
+        assert_eq!(parse(&["-p", "--", "-x"]), Ok(vec![("--path", vec!["-x"])]));
+        assert_eq!(parse(&["--path=--x"]), Ok(vec![("--path", vec!["--x"])]));
+        assert_eq!(
+            parse(&["--tags=a,b", "/x"]),
+            Ok(vec![("--path", vec!["/x"]), ("--tags", vec!["a", "b"])])
+        );
+        assert_eq!(
+            parse(&["-v", "--tags=--help"]),
+            Ok(vec![("--verbose", vec![]), ("--tags", vec!["--help"])])
+        );
+    }
+
+    #[test]
+    fn words_of_values() {
+        let w = |a: &[&'static str]| to_words(&pos(&expand(a, &s(), &u()), &["--path"]).unwrap());
+        assert_eq!(w(&["/x", "-v"]), ["--path", "/x", "--verbose"]);
+        assert_eq!(w(&["--", "-x"]), ["--path=-x"]);
+        assert_eq!(
+            w(&["--tags", "a", "--", "--b", "c"]),
+            ["--tags", "a", "--tags=--b", "--tags", "c"]
+        );
+        assert_eq!(w(&["--tags=--a,b"]), ["--tags=--a", "--tags", "b"]);
+    }
+
+    fn flag(a: &[&'static str], last: bool) -> Result<bool, String> {
+        let Ok(mut p) = ParsedArgs::new(a) else {
+            return Err("parse".to_owned());
+        };
+        if last {
+            p.last = vec!["--force"];
+        }
+        <bool as Parse2<()>>::parse2(Init::None, "--force", &(), &mut p).map_err(|e| e.to_string())
+    }
+    #[test]
+    fn flag_repeated() {
+        assert_eq!(flag(&["--force"], false), Ok(true));
+        assert_eq!(flag(&["--no-force"], false), Ok(false));
+        assert_eq!(flag(&[], false), Ok(false));
+        let r = Parse2Err::Repeated.to_string();
+        assert_eq!(flag(&["--force", "--force"], false), Err(r.to_owned()));
+        assert_eq!(flag(&["--no-force", "--no-force"], false), Err(r));
+        let n = Parse2Err::Negated.to_string();
+        assert_eq!(flag(&["--force", "--no-force"], false), Err(n));
+        assert_eq!(flag(&["--force", "--force"], true), Ok(true));
+        assert_eq!(flag(&["--force", "--no-force"], true), Ok(false));
+        assert_eq!(flag(&["--no-force", "--force"], true), Ok(true));
+    }
+
+    #[test]
+    fn prefixes() {
+        let c = ["headset", "help", "helpers"];
+        assert_eq!(unique_prefix("hea", &c), Ok(Some("headset")));
+        assert_eq!(unique_prefix("help", &c), Ok(Some("help")));
+        assert_eq!(
+            unique_prefix("he", &c),
+            Err(vec!["headset", "help", "helpers"])
+        );
+        assert_eq!(unique_prefix("x", &c), Ok(None));
+        assert_eq!(unique_prefix("", &c), Ok(None));
+    }
+    #[test]
+    fn unabbrev_keys() {
+        let k = ["--path", "--tags", "--tail"].map(|k| k.to_owned());
+        let o = Own::default();
+        let a = |a: &[&'static str]| match unabbrev(a, &k, &o) {
+            Ok(r) => Ok(r.join(" ")),
+            Err(ParsedArgsErr::Ambiguous(a, m)) => Err(format!("{a}: {}", m.join(" "))),
+            Err(ParsedArgsErr::UnexpectedToken(a)) => Err(a.to_owned()),
+        };
+        assert_eq!(
+            a(&["--pa", "x", "--tag=a,b"]),
+            Ok("--path x --tags=a,b".to_owned())
+        );
+        assert_eq!(a(&["--ta"]), Err("--ta: --tags --tail".to_owned()));
+        assert_eq!(a(&["pa", "--x"]), Ok("pa --x".to_owned()));
+        assert_eq!(a(&["--", "--pa"]), Ok("-- --pa".to_owned()));
+    }
+
+    fn take(a: &[&str]) -> (String, String) {
+        let (g, r) = take_opts(a, &u()[..3], &s());
+        (g.join(" ").replace(VAL, "="), r.join(" "))
+    }
+    #[test]
+    fn take_globals() {
+        assert_eq!(
+            take(&["headset", "-v", "dis", "--path", "x", "--dev", "y"]),
+            (
+                "--verbose --path = x".to_owned(),
+                "headset dis --dev y".to_owned()
+            )
+        );
+        assert_eq!(
+            take(&["--path=x", "--no-force"]),
+            ("--path = x --no-force".to_owned(), "".to_owned())
+        );
+        assert_eq!(
+            take(&["-p", "x", "a"]),
+            ("--path = x".to_owned(), "a".to_owned())
+        );
+        assert_eq!(
+            take(&["a", "--", "-v"]),
+            ("".to_owned(), "a -- -v".to_owned())
+        );
+    }
+    #[test]
+    fn take_globals_limits() {
+        // Bundles are left to the leaf, and values spelled like a global are taken.
+        assert_eq!(take(&["-vf"]), ("".to_owned(), "-vf".to_owned()));
+        assert_eq!(
+            take(&["--dev", "--force"]),
+            ("--force".to_owned(), "--dev".to_owned())
+        );
+    }
+
+    #[test]
+    fn leading_opts() {
+        let u = &u()[1..3];
+        assert_eq!(
+            leading(&["--force", "--path", "x", "con", "--y"], u),
+            Ok((vec![("--force", None), ("--path", Some("x"))], 3))
+        );
+        assert_eq!(
+            leading(&["--path=x", "--no-force", "con"], u),
+            Ok((vec![("--path", Some("x")), ("--no-force", None)], 2))
+        );
+        assert_eq!(leading(&["con", "--force"], u), Ok((vec![], 0)));
+        assert_eq!(leading(&["--", "--force"], u), Ok((vec![], 0)));
+        assert_eq!(leading(&["--nope", "con"], u), Err("--nope"));
+    }
+    #[test]
+    fn inherit_opts() {
+        let o = [("--force", None), ("--path", Some("x"))];
+        let u = &u()[..3];
+        assert_eq!(
+            inherit(vec!["a"], &o, u),
+            Ok(vec!["--force", "--path", VAL, "x", "a"])
+        );
+        assert_eq!(
+            inherit(vec!["--no-force", "--path", "y"], &o, u),
+            Ok(vec!["--no-force", "--path", "y"])
+        );
+        assert_eq!(inherit(vec![], &o, &u[2..]), Err(vec!["--force"]));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::com::*;
+
+/// Commands of `Acts::repl` that are not acts.
+pub const BUILTINS: [&str; 3] = ["help", "history", "exit"];
+
+/// Splits `l` into words like a POSIX shell would, without expansions.
+pub fn words(l: &str) -> Result<Vec<String>, String> {
+    let mut r = vec![];
+    let mut w = String::new();
+    let mut open = false;
+    let mut it = l.chars();
+    while let Some(c) = it.next() {
+        match c {
+            '\'' => {
+                open = true;
+                loop {
+                    match it.next() {
+                        Some('\'') => break,
+                        Some(c) => w.push(c),
+                        None => return Err("Unterminated quote.".to_owned()),
+                    }
+                }
+            }
+            '"' => {
+                open = true;
+                loop {
+                    match it.next() {
+                        Some('"') => break,
+                        Some('\\') => match it.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => w.push(c),
+                            Some(c) => {
+                                w.push('\\');
+                                w.push(c);
+                            }
+                            None => return Err("Unterminated quote.".to_owned()),
+                        },
+                        Some(c) => w.push(c),
+                        None => return Err("Unterminated quote.".to_owned()),
+                    }
+                }
+            }
+            '\\' => {
+                open = true;
+                match it.next() {
+                    Some(c) => w.push(c),
+                    None => return Err("Trailing backslash.".to_owned()),
+                }
+            }
+            c if c.is_whitespace() => {
+                if open {
+                    r.push(std::mem::take(&mut w));
+                    open = false;
+                }
+            }
+            c => {
+                open = true;
+                w.push(c);
+            }
+        }
+    }
+    if open {
+        r.push(w);
+    }
+    Ok(r)
+}
+
+/// Completes lines of `Acts::repl` through `f`, which is `Acts::complete`, and from earlier lines `h`.
+pub struct Line<'c, C> {
+    pub c: &'c C,
+    pub f: fn(&C, &[Arg]) -> Vec<String>,
+    pub h: Vec<String>,
+}
+impl<'c, C> Clone for Line<'c, C> {
+    fn clone(&self) -> Self {
+        Self {
+            c: self.c,
+            f: self.f,
+            h: self.h.clone(),
+        }
+    }
+}
+impl<'c, C> Line<'c, C> {
+    fn suggest(&self, i: &str) -> Vec<String> {
+        let (head, w) = match i.rfind(char::is_whitespace) {
+            Some(n) => i.split_at(n + 1),
+            None => ("", i),
+        };
+        let mut a = words(head).unwrap_or_default();
+        a.push(w.to_owned());
+        let a = a.iter().map(|a| a.as_str()).collect_vec();
+
+        let mut c = (self.f)(self.c, &a);
+        if a.len() == 1 {
+            c.extend(
+                BUILTINS
+                    .iter()
+                    .filter(|b| b.starts_with(w))
+                    .map(|b| b.to_string()),
+            );
+        }
+        self.h
+            .iter()
+            .rev()
+            .filter(|h| !i.is_empty() && h.starts_with(i) && *h != i)
+            .cloned()
+            .chain(c.into_iter().map(|c| {
+                let s = if c.ends_with('/') { "" } else { " " };
+                format!("{head}{}{s}", sh_quote(&c))
+            }))
+            .unique()
+            .collect()
+    }
+}
+impl<'c, C> Autocomplete for Line<'c, C> {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        Ok(self.suggest(input))
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        if let Some(e) = highlighted_suggestion {
+            return Ok(Replacement::Some(e));
+        }
+        let s = self.suggest(input);
+        let p = s.iter().skip(1).fold(s.first().cloned(), |p, s| {
+            let p = p?;
+            let n = p
+                .char_indices()
+                .zip(s.chars())
+                .find(|((_, a), b)| a != b)
+                .map(|((n, _), _)| n)
+                .unwrap_or(p.len().min(s.len()));
+            Some(p[..n].to_owned())
+        });
+        Ok(match p {
+            Some(p) if p.len() > input.len() => Replacement::Some(p),
+            _ => Replacement::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_quotes() {
+        let w = |l| words(l).map(|w| w.join("|"));
+        assert_eq!(w("a  b\tc"), Ok("a|b|c".to_owned()));
+        assert_eq!(
+            w(r#"a 'b c' "d \"e\" \n""#),
+            Ok(r#"a|b c|d "e" \n"#.to_owned())
+        );
+        assert_eq!(w(r"a\ b 'c\d'"), Ok(r"a b|c\d".to_owned()));
+        assert_eq!(w("'' a\"\"b"), Ok("|ab".to_owned()));
+        assert_eq!(w("a 'b"), Err("Unterminated quote.".to_owned()));
+        assert_eq!(w("a \"b"), Err("Unterminated quote.".to_owned()));
+        assert_eq!(w("a\\"), Err("Trailing backslash.".to_owned()));
+    }
+    #[test]
+    fn words_of_quoted() {
+        for s in ["a b", "it's", "", "$x", r#"\"#] {
+            assert_eq!(words(&sh_quote(s)), Ok(vec![s.to_owned()]));
+        }
+        assert_eq!(sh_quote("--dev=/dev/sde1"), "--dev=/dev/sde1");
+        assert_eq!(sh_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -25,6 +25,40 @@ pub fn to_lines<const S: usize, I: AsRef<str>>(a: &[[I; S]]) -> Vec<String> {
         })
         .collect()
 }
+/// Width of the terminal, or 80 when there is none.
+pub fn columns() -> usize {
+    crossterm::terminal::size()
+        .map(|(w, _)| w as usize)
+        .unwrap_or(80)
+}
+/// Wraps every line of `s` at word boundaries to fit `w` columns after an indent of `i`.
+pub fn wrap(s: &str, w: usize, i: usize) -> String {
+    use unicode_width::*;
+    let p = " ".repeat(i);
+    let w = w.saturating_sub(i).max(20);
+    s.lines()
+        .map(|l| {
+            let mut r = vec![];
+            let mut c = String::new();
+            for e in l.split_whitespace() {
+                if !c.is_empty() && c.width() + 1 + e.width() > w {
+                    r.push(std::mem::take(&mut c));
+                }
+                if !c.is_empty() {
+                    c.push(' ');
+                }
+                c.push_str(e);
+            }
+            r.push(c);
+            r.into_iter()
+                .map(|l| match l.is_empty() {
+                    true => l,
+                    false => format!("{p}{l}"),
+                })
+                .join("\n")
+        })
+        .join("\n")
+}
 pub fn to_table<const S: usize, I: AsRef<str>>(a: &[[I; S]]) -> String {
     to_lines(a).join("\n")
 }
@@ -203,6 +237,38 @@ where
         })
     }
 }
+impl<C: TimeZone> Default for MyDateTime<C>
+where
+    DateTime<C>: From<DateTime<FixedOffset>>,
+{
+    fn default() -> Self {
+        Self {
+            v: DateTime::<FixedOffset>::default().into(),
+        }
+    }
+}
+impl<'a, C: TimeZone> Parse<'a> for MyDateTime<C>
+where
+    DateTime<C>: From<DateTime<FixedOffset>>,
+{
+    fn parse(i: Arg<'a>) -> Result<Self, ParseErr<'a>> {
+        Self::from_str(i).map_err(|e| ParseErr {
+            i,
+            ty: Self::desc(),
+            e,
+        })
+    }
+
+    fn desc() -> &'static str {
+        "DateTime"
+    }
+    fn prompt(p: &str, d: Option<&str>) -> Result<String, InquireError> {
+        Ok(input_date::<C>(p)
+            .with_starting_input(d.unwrap_or(""))
+            .prompt()?
+            .to_string())
+    }
+}
 impl<C: TimeZone> Display for MyDateTime<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -328,6 +394,83 @@ impl<I: Clone, const S: usize> Autocomplete for TextWithAutocomplete<I, S> {
     }
 }
 
+/// Edit distance between `a` and `b`, where swapping two neighbouring letters, as in `hlep`, is one edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect_vec();
+    let b = b.chars().collect_vec();
+    let mut d = vec![(0..=b.len()).collect_vec(); a.len() + 1];
+    for (i, r) in d.iter_mut().enumerate() {
+        r[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let mut n = (d[i - 1][j - 1] + (a[i - 1] != b[j - 1]) as usize)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                n = n.min(d[i - 2][j - 2] + 1);
+            }
+            d[i][j] = n;
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The candidate that `i` is most likely a typo of, if any.
+/// Close spellings win, then fuzzy matches such as a missing or extra letter.
+pub fn suggest<'s, S: AsRef<str>>(i: &str, c: &'s [S]) -> Option<&'s str> {
+    let near = c
+        .iter()
+        .map(|c| (c.as_ref(), distance(c.as_ref(), i)))
+        .filter(|(c, d)| *d <= 1.max(c.len() / 3))
+        .min_by_key(|(_, d)| *d);
+    if let Some((c, _)) = near {
+        return Some(c);
+    }
+    let m = SkimMatcherV2::default();
+    c.iter()
+        .map(|c| c.as_ref())
+        .filter(|c| c.len().min(i.len()) * 2 >= c.len().max(i.len()))
+        .filter_map(|c| {
+            let s = m.fuzzy_match(c, i).max(m.fuzzy_match(i, c))?;
+            Some((c, s))
+        })
+        .max_by_key(|(_, s)| *s)
+        .map(|(c, _)| c)
+}
+
+/// The program name, without its directory.
+pub fn bin() -> String {
+    base(&args().next().unwrap_or_default()).to_owned()
+}
+/// The last component of the path `p`.
+pub fn base(p: &str) -> &str {
+    Path::new(p)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(p)
+}
+
+/// Quotes `s` for a POSIX shell, unless it is safe as is.
+pub fn sh_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        s.to_owned()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+/// Set to anything but `0` to never prompt, like `--no-input`.
+pub const NO_INPUT_ENV: &str = "NO_INPUT";
+
+/// Whether prompts can be shown, i.e. stdin and stdout are terminals and `NO_INPUT_ENV` is unset.
+pub fn interactive() -> bool {
+    use std::io::IsTerminal;
+    let off = std::env::var(NO_INPUT_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+    !off && std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
 type Res<T> = Result<T, MyErr>;
 pub fn env_var(s: &str) -> Res<String> {
     Ok(std::env::var(s).map_err(|e| format!("Failed to get env '{s}' because '{e}'"))?)
@@ -343,6 +486,20 @@ pub fn fs_write<P: AsRef<Path>, C: AsRef<[u8]>>(p: P, c: C) -> Res<()> {
         )
     })?)
 }
+pub fn fs_append<P: AsRef<Path>, C: AsRef<[u8]>>(p: P, c: C) -> Res<()> {
+    use std::io::Write;
+    Ok(std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(p.as_ref())
+        .and_then(|mut f| f.write_all(c.as_ref()))
+        .map_err(|e| {
+            format!(
+                "Failed to append to '{}' because '{e}'",
+                p.as_ref().to_string_lossy()
+            )
+        })?)
+}
 pub fn fs_read<P: AsRef<Path>>(p: P) -> Res<Vec<u8>> {
     Ok(std::fs::read(p.as_ref()).map_err(|e| {
         format!(
@@ -351,6 +508,25 @@ pub fn fs_read<P: AsRef<Path>>(p: P) -> Res<Vec<u8>> {
         )
     })?)
 }
+pub fn fs_read_json<P: AsRef<Path>>(p: P) -> Res<serde_json::Value> {
+    Ok(serde_json::from_slice(&fs_read(p.as_ref())?).map_err(|e| {
+        format!(
+            "Failed to parse '{}' because '{e}'",
+            p.as_ref().to_string_lossy()
+        )
+    })?)
+}
+/// Like `fs_read_json`, for a TOML file such as `[headset.dis] path = "/dev/sde1"`.
+pub fn fs_read_toml<P: AsRef<Path>>(p: P) -> Res<serde_json::Value> {
+    Ok(
+        toml::from_str(&String::from_utf8_lossy(&fs_read(p.as_ref())?)).map_err(|e| {
+            format!(
+                "Failed to parse '{}' because '{e}'",
+                p.as_ref().to_string_lossy()
+            )
+        })?,
+    )
+}
 pub fn fs_read_dir<P: AsRef<Path>>(p: P) -> Res<ReadDir> {
     Ok(std::fs::read_dir(p.as_ref()).map_err(|e| {
         format!(
@@ -359,3 +535,33 @@ pub fn fs_read_dir<P: AsRef<Path>>(p: P) -> Res<ReadDir> {
         )
     })?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_close() {
+        let c = ["headset", "help", "printscreen"];
+        assert_eq!(suggest("hedset", &c), Some("headset"));
+        assert_eq!(suggest("hlep", &c), Some("help"));
+        assert_eq!(suggest("printscren", &c), Some("printscreen"));
+        assert_eq!(suggest("--pth", &["--path", "--tags"]), Some("--path"));
+    }
+    #[test]
+    fn suggest_none() {
+        let c = ["headset", "help"];
+        assert_eq!(suggest("zzz", &c), None);
+        assert_eq!(suggest("h", &c), None);
+        assert_eq!(suggest("x", &[] as &[&str]), None);
+    }
+    #[test]
+    fn wrap_words() {
+        let s = "aaaa bbbb cccc dddd eeee";
+        assert_eq!(wrap(s, 20, 0), "aaaa bbbb cccc dddd\neeee");
+        assert_eq!(wrap(s, 24, 4), "    aaaa bbbb cccc dddd\n    eeee");
+        assert_eq!(wrap("a\n\nb", 80, 2), "  a\n\n  b");
+        assert_eq!(wrap("x".repeat(30).as_str(), 20, 0), "x".repeat(30));
+        assert_eq!(wrap("a b", 0, 0), "a b");
+    }
+}